@@ -0,0 +1,48 @@
+use crate::lib::{
+    ckbtc::OwnerArgs,
+    signing::{sign_ingress_with_request_status_query, IngressWithRequestId},
+    AnyhowResult,
+};
+use anyhow::anyhow;
+use candid::{Encode, Principal};
+use clap::Parser;
+use ic_agent::Agent;
+
+/// Signs the request of the Bitcoin address to deposit BTC to, for minting ckBTC.
+#[derive(Parser)]
+pub struct Opts {
+    /// The owner of the ckBTC account to mint to, if not the signer.
+    #[clap(long)]
+    owner: Option<Principal>,
+
+    /// The subaccount of the ckBTC account to mint to, as hex.
+    #[clap(long)]
+    subaccount: Option<String>,
+}
+
+pub fn exec(
+    agent: Agent,
+    minter: Principal,
+    opts: Opts,
+) -> AnyhowResult<Vec<IngressWithRequestId>> {
+    let args = Encode!(&OwnerArgs {
+        owner: opts.owner,
+        subaccount: opts.subaccount.map(parse_subaccount).transpose()?,
+    })?;
+    Ok(vec![sign_ingress_with_request_status_query(
+        agent,
+        minter,
+        "get_btc_address",
+        args,
+    )?])
+}
+
+/// Parses a 32-byte subaccount given as hex.
+pub fn parse_subaccount(subaccount: String) -> AnyhowResult<Vec<u8>> {
+    let bytes = hex::decode(&subaccount)
+        .map_err(|err| anyhow!("Couldn't parse the subaccount: {}", err))?;
+    if bytes.len() != 32 {
+        return Err(anyhow!("The subaccount must be 32 bytes long"));
+    }
+    Ok(bytes)
+}
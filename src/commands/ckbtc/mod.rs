@@ -0,0 +1,53 @@
+//! Commands for the ckBTC minter and ledger.
+use crate::{commands::print, lib::AnyhowResult};
+use candid::Principal;
+use clap::Parser;
+use ic_agent::Agent;
+
+mod get_btc_address;
+mod retrieve_btc_status;
+mod update_balance;
+mod withdraw;
+
+/// Signs messages to the ckBTC minter and ledger, to mint ckBTC from Bitcoin deposits
+/// and to withdraw ckBTC to Bitcoin.
+#[derive(Parser)]
+pub struct Opts {
+    /// The id of the ckBTC minter canister.
+    #[clap(long, default_value = "mqygn-kiaaa-aaaar-qaadq-cai")]
+    minter_canister_id: Principal,
+
+    /// The id of the ckBTC ledger canister.
+    #[clap(long, default_value = "mxzaz-hqaaa-aaaar-qaada-cai")]
+    ledger_canister_id: Principal,
+
+    #[clap(subcommand)]
+    command: CkbtcCommand,
+}
+
+#[derive(Parser)]
+enum CkbtcCommand {
+    GetBtcAddress(get_btc_address::Opts),
+    UpdateBalance(update_balance::Opts),
+    Withdraw(withdraw::Opts),
+    RetrieveBtcStatus(retrieve_btc_status::Opts),
+}
+
+pub fn exec(agent: Agent, opts: Opts) -> AnyhowResult {
+    let minter = opts.minter_canister_id;
+    let ledger = opts.ledger_canister_id;
+    match opts.command {
+        CkbtcCommand::GetBtcAddress(opts) => {
+            get_btc_address::exec(agent, minter, opts).and_then(|out| print(&out))
+        }
+        CkbtcCommand::UpdateBalance(opts) => {
+            update_balance::exec(agent, minter, opts).and_then(|out| print(&out))
+        }
+        CkbtcCommand::Withdraw(opts) => {
+            withdraw::exec(agent, minter, ledger, opts).and_then(|out| print(&out))
+        }
+        CkbtcCommand::RetrieveBtcStatus(opts) => {
+            retrieve_btc_status::exec(agent, minter, opts).and_then(|out| print(&out))
+        }
+    }
+}
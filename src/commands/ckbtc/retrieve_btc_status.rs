@@ -0,0 +1,31 @@
+use crate::lib::{
+    ckbtc::RetrieveBtcStatusArgs,
+    is_query,
+    signing::{sign_ingress, Ingress},
+    AnyhowResult,
+};
+use candid::{Encode, Principal};
+use clap::Parser;
+use ic_agent::Agent;
+
+/// Signs the query for the status of a withdrawal.
+#[derive(Parser)]
+pub struct Opts {
+    /// The block index returned by the minter when the withdrawal was accepted.
+    #[clap(long)]
+    block_index: u64,
+}
+
+pub fn exec(agent: Agent, minter: Principal, opts: Opts) -> AnyhowResult<Vec<Ingress>> {
+    let args = Encode!(&RetrieveBtcStatusArgs {
+        block_index: opts.block_index,
+    })?;
+    let method_name = "retrieve_btc_status";
+    Ok(vec![sign_ingress(
+        agent,
+        minter,
+        method_name,
+        is_query(minter, method_name),
+        args,
+    )?])
+}
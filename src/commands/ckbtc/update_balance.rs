@@ -0,0 +1,41 @@
+use crate::{
+    commands::ckbtc::get_btc_address::parse_subaccount,
+    lib::{
+        ckbtc::OwnerArgs,
+        signing::{sign_ingress_with_request_status_query, IngressWithRequestId},
+        AnyhowResult,
+    },
+};
+use candid::{Encode, Principal};
+use clap::Parser;
+use ic_agent::Agent;
+
+/// Signs the request to mint ckBTC for the confirmed deposits to the Bitcoin address of
+/// the account.
+#[derive(Parser)]
+pub struct Opts {
+    /// The owner of the ckBTC account to mint to, if not the signer.
+    #[clap(long)]
+    owner: Option<Principal>,
+
+    /// The subaccount of the ckBTC account to mint to, as hex.
+    #[clap(long)]
+    subaccount: Option<String>,
+}
+
+pub fn exec(
+    agent: Agent,
+    minter: Principal,
+    opts: Opts,
+) -> AnyhowResult<Vec<IngressWithRequestId>> {
+    let args = Encode!(&OwnerArgs {
+        owner: opts.owner,
+        subaccount: opts.subaccount.map(parse_subaccount).transpose()?,
+    })?;
+    Ok(vec![sign_ingress_with_request_status_query(
+        agent,
+        minter,
+        "update_balance",
+        args,
+    )?])
+}
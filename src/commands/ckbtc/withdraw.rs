@@ -0,0 +1,76 @@
+use crate::{
+    commands::transfer::parse_icpts,
+    lib::{
+        ckbtc::{RetrieveBtcWithApprovalArgs, TRANSACTION_FEE},
+        icrc1::{Account, ApproveArgs},
+        signing::{sign_ingress_with_request_status_query, IngressWithRequestId},
+        AnyhowResult,
+    },
+};
+use anyhow::anyhow;
+use candid::{Encode, Nat, Principal};
+use clap::Parser;
+use ic_agent::Agent;
+
+/// Signs the withdrawal of ckBTC to a Bitcoin address: the approval for the minter to
+/// burn the amount on the ledger and the request to send the BTC.
+#[derive(Parser)]
+pub struct Opts {
+    /// The Bitcoin address to send the BTC to.
+    #[clap(long)]
+    address: String,
+
+    /// Amount of ckBTC to withdraw (with up to 8 decimal digits after comma).
+    #[clap(long)]
+    amount: String,
+
+    /// Only sign the request to the minter, for an approval that was already given.
+    #[clap(long)]
+    skip_approval: bool,
+}
+
+pub fn exec(
+    agent: Agent,
+    minter: Principal,
+    ledger: Principal,
+    opts: Opts,
+) -> AnyhowResult<Vec<IngressWithRequestId>> {
+    let amount = parse_icpts(&opts.amount)
+        .map_err(|err| anyhow!("Couldn't parse the amount: {}", err))?
+        .get_e8s();
+    let mut messages = Vec::new();
+    if !opts.skip_approval {
+        // The allowance covers the fee of the burn in addition to the amount.
+        let args = Encode!(&ApproveArgs {
+            fee: None,
+            memo: None,
+            from_subaccount: None,
+            created_at_time: None,
+            amount: Nat::from(amount + TRANSACTION_FEE),
+            expected_allowance: None,
+            expires_at: None,
+            spender: Account {
+                owner: minter,
+                subaccount: None,
+            },
+        })?;
+        messages.push(sign_ingress_with_request_status_query(
+            agent.clone(),
+            ledger,
+            "icrc2_approve",
+            args,
+        )?);
+    }
+    let args = Encode!(&RetrieveBtcWithApprovalArgs {
+        from_subaccount: None,
+        address: opts.address,
+        amount,
+    })?;
+    messages.push(sign_ingress_with_request_status_query(
+        agent,
+        minter,
+        "retrieve_btc_with_approval",
+        args,
+    )?);
+    Ok(messages)
+}
@@ -0,0 +1,69 @@
+use crate::{
+    commands::neuron_manage::parse_neuron_id,
+    lib::{
+        get_secp256k1_public_key, governance_canister_id,
+        signing::{sign_ingress_with_request_status_query, IngressWithRequestId},
+        AnyhowResult,
+    },
+};
+use anyhow::anyhow;
+use candid::Encode;
+use clap::Parser;
+use ic_agent::Agent;
+use ic_base_types::PrincipalId;
+use ic_nns_common::pb::v1::NeuronId;
+use ic_types::Principal;
+
+/// Signs a claim of genesis token neurons, which must be signed with the secp256k1 key
+/// of the seed-round account they were allocated to.
+#[derive(Parser)]
+pub struct Opts {
+    /// The ids of the neurons to claim.
+    #[clap(required = true)]
+    neuron_ids: Vec<String>,
+
+    /// The principal the neurons are claimed for. It must be the principal derived from
+    /// the signer's public key, which is the default.
+    #[clap(long)]
+    principal: Option<PrincipalId>,
+}
+
+pub fn exec(
+    agent: Agent,
+    pem: Option<String>,
+    opts: Opts,
+) -> AnyhowResult<Vec<IngressWithRequestId>> {
+    let principal = get_gtc_principal(pem)?;
+    if let Some(expected) = opts.principal {
+        if expected.0 != principal {
+            return Err(anyhow!(
+                "The principal {} isn't the principal {} of the signer's public key",
+                expected,
+                principal
+            ));
+        }
+    }
+    let neuron_ids = opts
+        .neuron_ids
+        .iter()
+        .map(|id| parse_neuron_id(id).map(|id| NeuronId { id }))
+        .collect::<AnyhowResult<Vec<_>>>()?;
+    let args = Encode!(&PrincipalId(principal), &neuron_ids)?;
+    Ok(vec![sign_ingress_with_request_status_query(
+        agent,
+        governance_canister_id(),
+        "claim_gtc_neurons",
+        args,
+    )?])
+}
+
+/// Returns the self-authenticating principal of the signer's secp256k1 public key, the
+/// principal genesis token neurons are allocated to.
+pub fn get_gtc_principal(pem: Option<String>) -> AnyhowResult<Principal> {
+    let pem = pem.ok_or_else(|| {
+        anyhow!("Genesis token neurons can only be managed with a seed or PEM file")
+    })?;
+    Ok(Principal::self_authenticating(get_secp256k1_public_key(
+        &pem,
+    )?))
+}
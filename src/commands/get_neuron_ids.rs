@@ -0,0 +1,20 @@
+use crate::{
+    lib::signing::{sign_ingress, Ingress},
+    lib::{governance_canister_id, AnyhowResult},
+};
+use candid::Encode;
+use ic_agent::Agent;
+
+/// Signs a query for the ids of all neurons controlled by the signer or having it as a hot key.
+pub fn exec(agent: Agent) -> AnyhowResult<Vec<Ingress>> {
+    let args = Encode!()?;
+    let method_name = "get_neuron_ids";
+    let canister_id = governance_canister_id();
+    Ok(vec![sign_ingress(
+        agent,
+        canister_id,
+        method_name,
+        crate::lib::is_query(canister_id, method_name),
+        args,
+    )?])
+}
@@ -0,0 +1,37 @@
+use crate::{
+    commands::neuron_manage::parse_neuron_id,
+    lib::signing::{sign_ingress, Ingress},
+    lib::{governance_canister_id, AnyhowResult},
+};
+use candid::Encode;
+use clap::Parser;
+use ic_agent::Agent;
+
+/// Signs a query for a single neuron. Any neuron's public information can be queried,
+/// including the neurons followed but not controlled by the signer.
+#[derive(Parser)]
+pub struct Opts {
+    /// The id of the neuron.
+    neuron_id: String,
+
+    /// Query the full neuron instead, which is only allowed for its controller and hot keys.
+    #[clap(long)]
+    full: bool,
+}
+
+pub fn exec(agent: Agent, opts: Opts) -> AnyhowResult<Vec<Ingress>> {
+    let args = Encode!(&parse_neuron_id(&opts.neuron_id)?)?;
+    let method_name = if opts.full {
+        "get_full_neuron"
+    } else {
+        "get_neuron_info"
+    };
+    let canister_id = governance_canister_id();
+    Ok(vec![sign_ingress(
+        agent,
+        canister_id,
+        method_name,
+        crate::lib::is_query(canister_id, method_name),
+        args,
+    )?])
+}
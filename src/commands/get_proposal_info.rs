@@ -0,0 +1,27 @@
+use crate::{
+    lib::signing::{sign_ingress, Ingress},
+    lib::{governance_canister_id, AnyhowResult},
+};
+use candid::Encode;
+use clap::Parser;
+use ic_agent::Agent;
+
+/// Signs a query for the details of a proposal.
+#[derive(Parser)]
+pub struct Opts {
+    /// The id of the proposal.
+    proposal_id: u64,
+}
+
+pub fn exec(agent: Agent, opts: Opts) -> AnyhowResult<Vec<Ingress>> {
+    let args = Encode!(&opts.proposal_id)?;
+    let method_name = "get_proposal_info";
+    let canister_id = governance_canister_id();
+    Ok(vec![sign_ingress(
+        agent,
+        canister_id,
+        method_name,
+        crate::lib::is_query(canister_id, method_name),
+        args,
+    )?])
+}
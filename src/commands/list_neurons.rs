@@ -1,11 +1,10 @@
 use crate::{
     lib::signing::{sign_ingress, Ingress},
-    lib::{governance_canister_id, AnyhowResult},
+    lib::{governance::ListNeurons, governance_canister_id, AnyhowResult},
 };
 use candid::Encode;
 use clap::Parser;
 use ic_agent::Agent;
-use ic_nns_governance::pb::v1::ListNeurons;
 
 /// Signs a neuron configuration change.
 #[derive(Parser)]
@@ -16,6 +15,22 @@ pub struct Opts {
     /// this option is to narrow the query, and not to allow querying of
     /// arbtirary neuron ids.
     neuron_id: Vec<u64>,
+
+    /// Include neurons without stake or maturity.
+    #[clap(long)]
+    include_empty: bool,
+
+    /// Include the full details of public neurons.
+    #[clap(long)]
+    include_public: bool,
+
+    /// The page to query, starting at 0, when the neurons are listed in pages.
+    #[clap(long)]
+    page: Option<u64>,
+
+    /// The number of neurons per page.
+    #[clap(long)]
+    page_size: Option<u64>,
 }
 
 // We currently only support a subset of the functionality.
@@ -23,6 +38,10 @@ pub fn exec(agent: Agent, opts: Opts) -> AnyhowResult<Vec<Ingress>> {
     let args = Encode!(&ListNeurons {
         neuron_ids: opts.neuron_id.clone(),
         include_neurons_readable_by_caller: opts.neuron_id.is_empty(),
+        include_empty_neurons_readable_by_caller: opts.include_empty.then(|| true),
+        include_public_neurons_in_full_neurons: opts.include_public.then(|| true),
+        page_number: opts.page,
+        page_size: opts.page_size,
     })?;
     let method_name = "list_neurons";
     let canister_id = governance_canister_id();
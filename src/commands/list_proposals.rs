@@ -0,0 +1,69 @@
+use crate::{
+    lib::governance::{all_topics, parse_proposal_status, parse_topic, ListProposalInfo},
+    lib::signing::{sign_ingress, Ingress},
+    lib::{governance_canister_id, AnyhowResult},
+};
+use anyhow::anyhow;
+use candid::Encode;
+use clap::Parser;
+use ic_agent::Agent;
+use ic_nns_common::pb::v1::ProposalId;
+
+/// Signs a query for the most recent proposals, optionally filtered by topic and status.
+#[derive(Parser)]
+pub struct Opts {
+    /// Only list proposals with these topics, given by name (e.g. governance) or number.
+    #[clap(long)]
+    topic: Vec<String>,
+
+    /// Only list proposals with these statuses: open, rejected, adopted, executed or failed.
+    #[clap(long)]
+    status: Vec<String>,
+
+    /// The maximum number of proposals to list.
+    #[clap(long, default_value = "10")]
+    limit: u32,
+
+    /// Only list proposals older than the proposal with this id.
+    #[clap(long)]
+    before: Option<u64>,
+}
+
+pub fn exec(agent: Agent, opts: Opts) -> AnyhowResult<Vec<Ingress>> {
+    let mut topics = Vec::new();
+    for topic in &opts.topic {
+        topics.push(parse_topic(topic).ok_or_else(|| anyhow!("Unknown topic {}", topic))?);
+    }
+    let mut statuses = Vec::new();
+    for status in &opts.status {
+        statuses.push(
+            parse_proposal_status(status)
+                .ok_or_else(|| anyhow!("Unknown proposal status {}", status))?,
+        );
+    }
+    // The governance canister only supports excluding topics.
+    let exclude_topic = if topics.is_empty() {
+        Vec::new()
+    } else {
+        all_topics()
+            .into_iter()
+            .filter(|topic| !topics.contains(topic))
+            .collect()
+    };
+    let args = Encode!(&ListProposalInfo {
+        include_reward_status: Vec::new(),
+        before_proposal: opts.before.map(|id| ProposalId { id }),
+        limit: opts.limit,
+        exclude_topic,
+        include_status: statuses,
+    })?;
+    let method_name = "list_proposals";
+    let canister_id = governance_canister_id();
+    Ok(vec![sign_ingress(
+        agent,
+        canister_id,
+        method_name,
+        crate::lib::is_query(canister_id, method_name),
+        args,
+    )?])
+}
@@ -2,21 +2,38 @@
 
 use crate::{
     commands::raw::IngressMessage,
-    lib::{get_agent, AnyhowResult},
+    lib::{
+        duration::{parse_duration, set_now, warn_clock_skew},
+        get_agent, get_identity,
+        signing::{self, SigningConfig, MAX_INGRESS_EXPIRY_SECONDS},
+        AnyhowResult,
+    },
 };
 use anyhow::anyhow;
+use chrono::DateTime;
 use clap::Parser;
 use std::io::{self, Write};
 use tokio::runtime::Runtime;
 
+mod ckbtc;
+mod claim_gtc_neurons;
 mod generate;
+mod get_neuron_ids;
+mod get_neuron_info;
+mod get_pending_proposals;
+mod get_proposal_info;
 mod ids;
 mod list_neurons;
+mod list_proposals;
 mod neuron_manage;
+mod neuron_plan;
 mod neuron_stake;
 mod raw;
 mod send;
+mod sns;
+mod sns_participate;
 mod transfer;
+mod transfer_gtc_neuron;
 
 pub use ids::get_ids;
 
@@ -28,21 +45,66 @@ pub enum Command {
     Transfer(transfer::Opts),
     NeuronStake(neuron_stake::Opts),
     NeuronManage(neuron_manage::Opts),
+    NeuronPlan(neuron_plan::Opts),
     /// Signs the query for all neurons belonging to the signing principal.
     ListNeurons(list_neurons::Opts),
+    ListProposals(list_proposals::Opts),
+    /// Signs the query for all proposals open for voting.
+    GetPendingProposals,
+    GetProposalInfo(get_proposal_info::Opts),
+    GetNeuronInfo(get_neuron_info::Opts),
+    /// Signs the query for the ids of all neurons of the signing principal.
+    GetNeuronIds,
+    ClaimGtcNeurons(claim_gtc_neurons::Opts),
+    TransferGtcNeuron(transfer_gtc_neuron::Opts),
+    Sns(sns::Opts),
+    SnsParticipate(sns_participate::Opts),
+    Ckbtc(ckbtc::Opts),
     /// Generate a mnemonic seed phrase and generate or recover PEM.
     Generate(generate::Opts),
     /// Call a canister method directly
     Raw(raw::Opts),
 }
 
-pub fn exec(pem: Option<String>, cmd: Command) -> AnyhowResult {
+/// The validity of the signed messages, given on the command line.
+pub struct ExpiryOpts {
+    pub ingress_expiry: String,
+    pub windows: u32,
+    pub status_queries: u32,
+    pub now: Option<String>,
+}
+
+pub fn exec(pem: Option<String>, expiry: ExpiryOpts, cmd: Command) -> AnyhowResult {
     let runtime = Runtime::new().expect("Unable to create a runtime");
+    if let Some(now) = &expiry.now {
+        let now = DateTime::parse_from_rfc3339(now)
+            .map_err(|err| anyhow!("Couldn't parse --now as an RFC 3339 date: {}", err))?;
+        set_now(now.timestamp().max(1) as u64);
+    }
     match cmd {
         Command::Send(opts) => runtime.block_on(async { send::exec(opts).await }),
         Command::Generate(opts) => generate::exec(opts),
         cmd => {
-            let agent = runtime.block_on(async { get_agent(pem).await })?;
+            let ingress_expiry_seconds = parse_duration(&expiry.ingress_expiry)?;
+            if ingress_expiry_seconds == 0 || ingress_expiry_seconds > MAX_INGRESS_EXPIRY_SECONDS {
+                return Err(anyhow!(
+                    "The ingress expiry must be positive and at most 5 minutes"
+                ));
+            }
+            if expiry.windows == 0 {
+                return Err(anyhow!("At least one expiry window is needed"));
+            }
+            if expiry.status_queries == 0 {
+                return Err(anyhow!("At least one request status query is needed"));
+            }
+            warn_clock_skew();
+            signing::configure(SigningConfig {
+                identity: get_identity(pem.clone()),
+                ingress_expiry_seconds,
+                windows: expiry.windows,
+                status_queries: expiry.status_queries,
+            });
+            let agent = runtime.block_on(async { get_agent(pem.clone()).await })?;
             match cmd {
                 Command::PublicIds => ids::exec(agent),
                 Command::Transfer(opts) => transfer::exec(agent, opts).and_then(|out| print(&out)),
@@ -52,9 +114,36 @@ pub fn exec(pem: Option<String>, cmd: Command) -> AnyhowResult {
                 Command::NeuronManage(opts) => {
                     neuron_manage::exec(agent, opts).and_then(|out| print(&out))
                 }
+                Command::NeuronPlan(opts) => {
+                    neuron_plan::exec(agent, opts).and_then(|out| print(&out))
+                }
                 Command::ListNeurons(opts) => {
                     list_neurons::exec(agent, opts).and_then(|out| print(&out))
                 }
+                Command::ListProposals(opts) => {
+                    list_proposals::exec(agent, opts).and_then(|out| print(&out))
+                }
+                Command::GetPendingProposals => {
+                    get_pending_proposals::exec(agent).and_then(|out| print(&out))
+                }
+                Command::GetProposalInfo(opts) => {
+                    get_proposal_info::exec(agent, opts).and_then(|out| print(&out))
+                }
+                Command::GetNeuronInfo(opts) => {
+                    get_neuron_info::exec(agent, opts).and_then(|out| print(&out))
+                }
+                Command::GetNeuronIds => get_neuron_ids::exec(agent).and_then(|out| print(&out)),
+                Command::ClaimGtcNeurons(opts) => {
+                    claim_gtc_neurons::exec(agent, pem, opts).and_then(|out| print(&out))
+                }
+                Command::TransferGtcNeuron(opts) => {
+                    transfer_gtc_neuron::exec(agent, pem, opts).and_then(|out| print(&out))
+                }
+                Command::Sns(opts) => sns::exec(agent, opts),
+                Command::SnsParticipate(opts) => {
+                    sns_participate::exec(agent, opts).and_then(|out| print(&out))
+                }
+                Command::Ckbtc(opts) => ckbtc::exec(agent, opts),
                 Command::Raw(opts) => raw::exec(agent, opts).and_then(|out| match out {
                     IngressMessage::Ingress(msg) => print(&vec![msg]),
                     IngressMessage::IngressWithRequestId(msg) => print(&vec![msg]),
@@ -1,27 +1,69 @@
-use crate::lib::{
-    governance_canister_id,
-    signing::{sign_ingress_with_request_status_query, IngressWithRequestId},
-    AnyhowResult,
+use crate::{
+    commands::{
+        neuron_stake::{convert_name_to_nonce, neuron_name_validator},
+        transfer::parse_icpts,
+    },
+    lib::{
+        duration::{
+            now_seconds, parse_dissolve_delay, parse_timestamp, MAX_DISSOLVE_DELAY_SECONDS,
+        },
+        governance::{
+            format_e8s, parse_topic, parse_visibility, Account, ChangeAutoStakeMaturity,
+            ConfigureArgs, ConfigureOperation, DisburseMaturity, Empty as EmptyRecord,
+            ManageNeuronArgs, NeuronCommand, SetVisibility, StakeMaturity,
+        },
+        governance_canister_id, read_from_file,
+        signing::{sign_ingress_with_request_status_query, IngressWithRequestId},
+        validation::{self, Finding, MIN_STAKE_E8S},
+        AnyhowResult,
+    },
 };
 use anyhow::anyhow;
-use candid::Encode;
+use candid::{Encode, Principal};
 use clap::Parser;
 use ic_agent::Agent;
 use ic_base_types::PrincipalId;
-use ic_nns_common::pb::v1::NeuronId;
+use ic_nns_common::pb::v1::{NeuronId, ProposalId};
+use ic_nns_governance::governance::compute_neuron_staking_subaccount;
 use ic_nns_governance::pb::v1::{
     manage_neuron::{
-        configure::Operation, AddHotKey, Command, Configure, Disburse, IncreaseDissolveDelay,
-        Merge, MergeMaturity, RemoveHotKey, Split, StartDissolving, StopDissolving,
+        claim_or_refresh::By, configure::Operation, disburse::Amount, AddHotKey, ClaimOrRefresh,
+        Command, Configure, Disburse, DisburseToNeuron, Follow, IncreaseDissolveDelay,
+        JoinCommunityFund, Merge, MergeMaturity, NeuronIdOrSubaccount, RegisterVote, RemoveHotKey,
+        SetDissolveTimestamp, Spawn, Split, StartDissolving, StopDissolving,
     },
-    ManageNeuron,
+    Empty, ManageNeuron,
 };
+use ledger_canister::{AccountIdentifier, TRANSACTION_FEE};
+use serde::{de::IgnoredAny, Deserialize, Deserializer};
+use std::str::FromStr;
 
 /// Signs a neuron configuration change.
 #[derive(Parser)]
 pub struct Opts {
-    /// The id of the neuron to manage.
-    neuron_id: String,
+    /// The ids of the neurons to manage.
+    neuron_id: Vec<String>,
+
+    /// A file listing the neurons to manage, one per line: either a neuron id or one of
+    /// `name:<name>`, `nonce:<nonce>` or `subaccount:<hex>`. Lines starting with # are ignored.
+    #[clap(long)]
+    neurons_file: Option<String>,
+
+    /// The staking subaccount of the neuron to manage, in hex.
+    #[clap(long)]
+    subaccount: Option<String>,
+
+    /// The name of the neuron to manage, as used in `neuron-stake`.
+    #[clap(long, validator(neuron_name_validator))]
+    name: Option<String>,
+
+    /// The nonce of the neuron to manage, as used in `neuron-stake`.
+    #[clap(long, conflicts_with("name"))]
+    nonce: Option<u64>,
+
+    /// The controller of the neurons addressed by name or nonce, defaults to the signer.
+    #[clap(long)]
+    controller: Option<PrincipalId>,
 
     /// Principal to be used as a hot key.
     #[clap(long)]
@@ -31,10 +73,20 @@ pub struct Opts {
     #[clap(long)]
     remove_hot_key: Option<PrincipalId>,
 
-    /// Number of dissolve seconds to add.
+    /// Dissolve delay to add, in seconds or as a duration like "6m", "1y 3d" or "8y".
+    /// Use "max" to increase the dissolve delay to the maximum of 8 years.
     #[clap(short, long)]
     additional_dissolve_delay_seconds: Option<String>,
 
+    /// Set the dissolve timestamp, either as an RFC 3339 date (e.g. 2025-05-01T00:00:00Z)
+    /// or as a duration from now (e.g. "2y 6m").
+    #[clap(long)]
+    set_dissolve_timestamp: Option<String>,
+
+    /// Join the Internet Computer's community fund with this neuron's entire stake.
+    #[clap(long)]
+    join_community_fund: bool,
+
     /// Start dissolving.
     #[clap(long)]
     start_dissolving: bool,
@@ -62,142 +114,553 @@ pub struct Opts {
     /// Merge the percentage (between 1 and 100) of the maturity of a neuron into the current stake.
     #[clap(long)]
     merge_maturity: Option<u32>,
+
+    /// Stake the percentage (between 1 and 100) of the maturity of a neuron.
+    #[clap(long)]
+    stake_maturity: Option<u32>,
+
+    /// Automatically stake all future maturity of the neuron.
+    #[clap(long)]
+    enable_auto_stake_maturity: bool,
+
+    /// Stop automatically staking the maturity of the neuron.
+    #[clap(long)]
+    disable_auto_stake_maturity: bool,
+
+    /// Disburse the percentage (between 1 and 100) of the maturity of a neuron.
+    #[clap(long)]
+    disburse_maturity: Option<u32>,
+
+    /// The principal receiving the disbursed maturity, defaults to the controller.
+    #[clap(long)]
+    disburse_maturity_to: Option<PrincipalId>,
+
+    /// Refresh the voting power of the neuron, confirming its following settings.
+    #[clap(long)]
+    refresh_voting_power: bool,
+
+    /// Set the visibility of the neuron: public or private.
+    #[clap(long)]
+    set_visibility: Option<String>,
+
+    /// Sign the operations even if they fail the checks against the rules of the protocol.
+    #[clap(long)]
+    force: bool,
 }
 
-pub fn exec(agent: Agent, opts: Opts) -> AnyhowResult<Vec<IngressWithRequestId>> {
-    let mut msgs = Vec::new();
+/// A neuron addressed either by its id or by its staking subaccount.
+pub type Target = (Option<NeuronId>, Option<NeuronIdOrSubaccount>);
 
-    let id = Some(NeuronId {
-        id: parse_neuron_id(opts.neuron_id),
-    });
-    if opts.add_hot_key.is_some() {
-        let args = Encode!(&ManageNeuron {
-            id: id.clone(),
-            command: Some(Command::Configure(Configure {
-                operation: Some(Operation::AddHotKey(AddHotKey {
-                    new_hot_key: opts.add_hot_key
+/// A single neuron operation, as given by the command-line options or a plan file.
+#[derive(Clone, Debug, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum NeuronOperation {
+    AddHotKey(#[serde(deserialize_with = "deserialize_principal")] PrincipalId),
+    RemoveHotKey(#[serde(deserialize_with = "deserialize_principal")] PrincipalId),
+    StopDissolving,
+    StartDissolving,
+    IncreaseDissolveDelay(String),
+    SetDissolveTimestamp(String),
+    JoinCommunityFund,
+    Disburse {
+        amount: Option<String>,
+        to: Option<String>,
+    },
+    Spawn,
+    SpawnTo(#[serde(deserialize_with = "deserialize_principal")] PrincipalId),
+    Split(u64),
+    Merge(String),
+    MergeMaturity(u32),
+    Follow {
+        topic: String,
+        followees: Vec<String>,
+    },
+    RegisterVote {
+        proposal: u64,
+        vote: String,
+    },
+    ClaimOrRefresh,
+    StakeMaturity(u32),
+    ChangeAutoStakeMaturity(bool),
+    DisburseMaturity {
+        percentage: u32,
+        to: Option<String>,
+    },
+    RefreshVotingPower,
+    SetVisibility(String),
+    DisburseToNeuron {
+        amount: String,
+        dissolve_delay: String,
+        new_controller: Option<String>,
+        nonce: u64,
+        #[serde(default)]
+        kyc_verified: bool,
+    },
+    /// Only parsed so that plans listing a proposal are refused with a clear error.
+    MakeProposal(IgnoredAny),
+}
+
+impl NeuronOperation {
+    /// Returns the name of the operation as used in plan files.
+    pub fn name(&self) -> &'static str {
+        match self {
+            NeuronOperation::AddHotKey(_) => "add_hot_key",
+            NeuronOperation::RemoveHotKey(_) => "remove_hot_key",
+            NeuronOperation::StopDissolving => "stop_dissolving",
+            NeuronOperation::StartDissolving => "start_dissolving",
+            NeuronOperation::IncreaseDissolveDelay(_) => "increase_dissolve_delay",
+            NeuronOperation::SetDissolveTimestamp(_) => "set_dissolve_timestamp",
+            NeuronOperation::JoinCommunityFund => "join_community_fund",
+            NeuronOperation::Disburse { .. } => "disburse",
+            NeuronOperation::Spawn => "spawn",
+            NeuronOperation::SpawnTo(_) => "spawn_to",
+            NeuronOperation::Split(_) => "split",
+            NeuronOperation::Merge(_) => "merge",
+            NeuronOperation::MergeMaturity(_) => "merge_maturity",
+            NeuronOperation::Follow { .. } => "follow",
+            NeuronOperation::RegisterVote { .. } => "register_vote",
+            NeuronOperation::ClaimOrRefresh => "claim_or_refresh",
+            NeuronOperation::StakeMaturity(_) => "stake_maturity",
+            NeuronOperation::ChangeAutoStakeMaturity(_) => "change_auto_stake_maturity",
+            NeuronOperation::DisburseMaturity { .. } => "disburse_maturity",
+            NeuronOperation::RefreshVotingPower => "refresh_voting_power",
+            NeuronOperation::SetVisibility(_) => "set_visibility",
+            NeuronOperation::DisburseToNeuron { .. } => "disburse_to_neuron",
+            NeuronOperation::MakeProposal(_) => "make_proposal",
+        }
+    }
+
+    /// Checks the operation on the target against the rules of the protocol, for
+    /// operations signed by `signer`.
+    pub fn validate(&self, target: &Target, signer: PrincipalId) -> AnyhowResult<Vec<Finding>> {
+        let neuron = describe_target(target);
+        let target_id = match target {
+            (Some(id), _) | (_, Some(NeuronIdOrSubaccount::NeuronId(id))) => Some(id.id),
+            _ => None,
+        };
+        let mut findings = Vec::new();
+        match self {
+            NeuronOperation::AddHotKey(principal) if *principal == signer => {
+                findings.push(Finding::Warning(format!(
+                    "Neuron {}: the hot key {} is the signer, which already controls the neuron",
+                    neuron, principal
+                )))
+            }
+            NeuronOperation::RemoveHotKey(principal) if *principal == signer => {
+                findings.push(Finding::Warning(format!(
+                    "Neuron {}: {} is the signer, which controls the neuron and isn't one of its hot keys",
+                    neuron, principal
+                )))
+            }
+            NeuronOperation::Merge(source) => {
+                if target_id == Some(parse_neuron_id(source)?) {
+                    findings.push(Finding::Error(format!(
+                        "Neuron {}: can't merge a neuron into itself",
+                        neuron
+                    )));
+                }
+            }
+            NeuronOperation::Split(amount) => {
+                if amount.saturating_mul(100_000_000) < MIN_STAKE_E8S + TRANSACTION_FEE.get_e8s() {
+                    findings.push(Finding::Error(format!(
+                        "Neuron {}: a split of {} ICP leaves the new neuron with less than the minimum stake of 1 ICP after the fee",
+                        neuron, amount
+                    )));
+                }
+            }
+            NeuronOperation::DisburseToNeuron { amount, .. } => {
+                if let Ok(amount) = parse_icpts(amount) {
+                    if amount.get_e8s() < MIN_STAKE_E8S + TRANSACTION_FEE.get_e8s() {
+                        findings.push(Finding::Error(format!(
+                            "Neuron {}: a disburse of {} ICP leaves the new neuron with less than the minimum stake of 1 ICP after the fee",
+                            neuron,
+                            format_e8s(amount.get_e8s())
+                        )));
+                    }
+                }
+            }
+            NeuronOperation::Disburse {
+                amount: Some(amount),
+                ..
+            } => {
+                if let Ok(amount) = parse_icpts(amount) {
+                    if amount.get_e8s() <= TRANSACTION_FEE.get_e8s() {
+                        findings.push(Finding::Error(format!(
+                            "Neuron {}: the disbursed amount must be larger than the fee of {} ICP",
+                            neuron,
+                            format_e8s(TRANSACTION_FEE.get_e8s())
+                        )));
+                    }
+                }
+            }
+            NeuronOperation::Follow { followees, .. } => {
+                let followees = followees
+                    .iter()
+                    .map(|id| parse_neuron_id(id))
+                    .collect::<AnyhowResult<Vec<_>>>()?;
+                if followees.iter().any(|id| Some(*id) == target_id) {
+                    findings.push(Finding::Warning(format!(
+                        "Neuron {}: following itself has no effect",
+                        neuron
+                    )));
+                }
+            }
+            _ => (),
+        }
+        Ok(findings)
+    }
+
+    /// Encodes the `manage_neuron` argument applying this operation to the target.
+    pub fn encode(&self, target: &Target) -> AnyhowResult<Vec<u8>> {
+        let (id, neuron_id_or_subaccount) = target.clone();
+        if let Some(command) = self.to_current_command()? {
+            return Ok(Encode!(&ManageNeuronArgs {
+                id,
+                command: Some(command),
+                neuron_id_or_subaccount,
+            })?);
+        }
+        Ok(Encode!(&ManageNeuron {
+            id,
+            command: Some(self.to_command()?),
+            neuron_id_or_subaccount,
+        })?)
+    }
+
+    /// Builds the command for operations added to governance after the pinned
+    /// `ic-nns-governance` revision, or returns `None` for the other operations.
+    fn to_current_command(&self) -> AnyhowResult<Option<NeuronCommand>> {
+        let configure = |operation| {
+            NeuronCommand::Configure(ConfigureArgs {
+                operation: Some(operation),
+            })
+        };
+        let check_percentage = |percentage: u32| {
+            if percentage == 0 || percentage > 100 {
+                Err(anyhow!("The percentage must be a number from 1 to 100"))
+            } else {
+                Ok(percentage)
+            }
+        };
+        Ok(Some(match self {
+            NeuronOperation::StakeMaturity(percentage) => {
+                NeuronCommand::StakeMaturity(StakeMaturity {
+                    percentage_to_stake: Some(check_percentage(*percentage)?),
+                })
+            }
+            NeuronOperation::ChangeAutoStakeMaturity(enabled) => configure(
+                ConfigureOperation::ChangeAutoStakeMaturity(ChangeAutoStakeMaturity {
+                    requested_setting_for_auto_stake_maturity: *enabled,
+                }),
+            ),
+            NeuronOperation::DisburseMaturity { percentage, to } => {
+                NeuronCommand::DisburseMaturity(DisburseMaturity {
+                    percentage_to_disburse: check_percentage(*percentage)?,
+                    to_account: match to {
+                        Some(to) => Some(Account {
+                            owner: Some(Principal::from_text(to).map_err(|err| {
+                                anyhow!("Couldn't parse the principal {}: {}", to, err)
+                            })?),
+                            subaccount: None,
+                        }),
+                        None => None,
+                    },
+                })
+            }
+            NeuronOperation::RefreshVotingPower => {
+                NeuronCommand::RefreshVotingPower(EmptyRecord {})
+            }
+            NeuronOperation::SetVisibility(visibility) => {
+                configure(ConfigureOperation::SetVisibility(SetVisibility {
+                    visibility: Some(parse_visibility(visibility).ok_or_else(|| {
+                        anyhow!(
+                            "The visibility must be either public or private, not {:?}",
+                            visibility
+                        )
+                    })?),
                 }))
+            }
+            _ => return Ok(None),
+        }))
+    }
+
+    /// Builds the governance command for operations known to the pinned
+    /// `ic-nns-governance` revision.
+    fn to_command(&self) -> AnyhowResult<Command> {
+        let configure = |operation| {
+            Command::Configure(Configure {
+                operation: Some(operation),
+            })
+        };
+        Ok(match self {
+            NeuronOperation::AddHotKey(principal) => configure(Operation::AddHotKey(AddHotKey {
+                new_hot_key: Some(*principal),
             })),
-            neuron_id_or_subaccount: None,
-        })?;
-        msgs.push(args);
+            NeuronOperation::RemoveHotKey(principal) => {
+                configure(Operation::RemoveHotKey(RemoveHotKey {
+                    hot_key_to_remove: Some(*principal),
+                }))
+            }
+            NeuronOperation::StopDissolving => {
+                configure(Operation::StopDissolving(StopDissolving {}))
+            }
+            NeuronOperation::StartDissolving => {
+                configure(Operation::StartDissolving(StartDissolving {}))
+            }
+            NeuronOperation::IncreaseDissolveDelay(delay) => {
+                configure(Operation::IncreaseDissolveDelay(IncreaseDissolveDelay {
+                    additional_dissolve_delay_seconds: parse_dissolve_delay(delay)?,
+                }))
+            }
+            NeuronOperation::SetDissolveTimestamp(timestamp) => {
+                let dissolve_timestamp_seconds = parse_timestamp(timestamp)?;
+                let now = now_seconds();
+                if dissolve_timestamp_seconds <= now {
+                    return Err(anyhow!("The dissolve timestamp must be in the future"));
+                }
+                if dissolve_timestamp_seconds - now > MAX_DISSOLVE_DELAY_SECONDS {
+                    return Err(anyhow!(
+                        "The dissolve timestamp must be at most 8 years from now"
+                    ));
+                }
+                configure(Operation::SetDissolveTimestamp(SetDissolveTimestamp {
+                    dissolve_timestamp_seconds,
+                }))
+            }
+            NeuronOperation::JoinCommunityFund => {
+                configure(Operation::JoinCommunityFund(JoinCommunityFund {}))
+            }
+            NeuronOperation::Disburse { amount, to } => Command::Disburse(Disburse {
+                to_account: match to {
+                    Some(to) => Some(
+                        AccountIdentifier::from_str(to)
+                            .map_err(|err| anyhow!(err))?
+                            .into(),
+                    ),
+                    None => None,
+                },
+                amount: match amount {
+                    Some(amount) => Some(Amount {
+                        e8s: parse_icpts(amount)
+                            .map_err(|err| anyhow!("Couldn't parse the amount: {}", err))?
+                            .get_e8s(),
+                    }),
+                    None => None,
+                },
+            }),
+            NeuronOperation::Spawn => Command::Spawn(Default::default()),
+            NeuronOperation::SpawnTo(principal) => Command::Spawn(Spawn {
+                new_controller: Some(*principal),
+            }),
+            NeuronOperation::Split(amount) => Command::Split(Split {
+                amount_e8s: amount
+                    .checked_mul(100_000_000)
+                    .ok_or_else(|| anyhow!("The amount of {} ICP to split is too large", amount))?,
+            }),
+            NeuronOperation::Merge(neuron_id) => Command::Merge(Merge {
+                source_neuron_id: Some(NeuronId {
+                    id: parse_neuron_id(neuron_id)?,
+                }),
+            }),
+            NeuronOperation::MergeMaturity(percentage_to_merge) => {
+                if *percentage_to_merge == 0 || *percentage_to_merge > 100 {
+                    return Err(anyhow!(
+                        "Percentage to merge must be a number from 1 to 100"
+                    ));
+                }
+                Command::MergeMaturity(MergeMaturity {
+                    percentage_to_merge: *percentage_to_merge,
+                })
+            }
+            NeuronOperation::Follow { topic, followees } => Command::Follow(Follow {
+                topic: parse_topic(topic).ok_or_else(|| anyhow!("Unknown topic {}", topic))?,
+                followees: followees
+                    .iter()
+                    .map(|id| parse_neuron_id(id).map(|id| NeuronId { id }))
+                    .collect::<AnyhowResult<_>>()?,
+            }),
+            NeuronOperation::RegisterVote { proposal, vote } => {
+                Command::RegisterVote(RegisterVote {
+                    proposal: Some(ProposalId { id: *proposal }),
+                    vote: match vote.to_lowercase().as_str() {
+                        "yes" | "y" | "1" => 1,
+                        "no" | "n" | "2" => 2,
+                        _ => return Err(anyhow!("The vote must be either yes or no")),
+                    },
+                })
+            }
+            NeuronOperation::ClaimOrRefresh => Command::ClaimOrRefresh(ClaimOrRefresh {
+                by: Some(By::NeuronIdOrSubaccount(Empty {})),
+            }),
+            NeuronOperation::DisburseToNeuron {
+                amount,
+                dissolve_delay,
+                new_controller,
+                nonce,
+                kyc_verified,
+            } => Command::DisburseToNeuron(DisburseToNeuron {
+                new_controller: match new_controller {
+                    Some(principal) => Some(PrincipalId::from_str(principal).map_err(|err| {
+                        anyhow!("Couldn't parse the principal {}: {:?}", principal, err)
+                    })?),
+                    None => None,
+                },
+                amount_e8s: parse_icpts(amount)
+                    .map_err(|err| anyhow!("Couldn't parse the amount: {}", err))?
+                    .get_e8s(),
+                dissolve_delay_seconds: u64::from(parse_dissolve_delay(dissolve_delay)?),
+                kyc_verified: *kyc_verified,
+                nonce: *nonce,
+            }),
+            NeuronOperation::MakeProposal(_) => {
+                return Err(anyhow!(
+                    "make_proposal isn't supported, proposals can't be signed from a plan"
+                ))
+            }
+            operation => {
+                return Err(anyhow!(
+                    "{} isn't supported by the pinned governance types",
+                    operation.name()
+                ))
+            }
+        })
+    }
+}
+
+pub fn exec(agent: Agent, opts: Opts) -> AnyhowResult<Vec<IngressWithRequestId>> {
+    let targets = parse_targets(&agent, &opts)?;
+    let mut operations = Vec::new();
+
+    if let Some(principal) = opts.add_hot_key {
+        operations.push(NeuronOperation::AddHotKey(principal));
     };
 
-    if opts.remove_hot_key.is_some() {
-        let args = Encode!(&ManageNeuron {
-            id: id.clone(),
-            command: Some(Command::Configure(Configure {
-                operation: Some(Operation::RemoveHotKey(RemoveHotKey {
-                    hot_key_to_remove: opts.remove_hot_key
-                }))
-            })),
-            neuron_id_or_subaccount: None,
-        })?;
-        msgs.push(args);
+    if let Some(principal) = opts.remove_hot_key {
+        operations.push(NeuronOperation::RemoveHotKey(principal));
     };
 
     if opts.stop_dissolving {
-        let args = Encode!(&ManageNeuron {
-            id: id.clone(),
-            command: Some(Command::Configure(Configure {
-                operation: Some(Operation::StopDissolving(StopDissolving {}))
-            })),
-            neuron_id_or_subaccount: None,
-        })?;
-        msgs.push(args);
+        operations.push(NeuronOperation::StopDissolving);
     }
 
     if opts.start_dissolving {
-        let args = Encode!(&ManageNeuron {
-            id: id.clone(),
-            command: Some(Command::Configure(Configure {
-                operation: Some(Operation::StartDissolving(StartDissolving {}))
-            })),
-            neuron_id_or_subaccount: None,
-        })?;
-        msgs.push(args);
+        operations.push(NeuronOperation::StartDissolving);
     }
 
     if let Some(additional_dissolve_delay_seconds) = opts.additional_dissolve_delay_seconds {
-        let args = Encode!(&ManageNeuron {
-            id: id.clone(),
-            command: Some(Command::Configure(Configure {
-                operation: Some(Operation::IncreaseDissolveDelay(IncreaseDissolveDelay {
-                    additional_dissolve_delay_seconds: additional_dissolve_delay_seconds
-                        .parse::<u32>()
-                        .expect("Couldn't parse the dissolve delay"),
-                }))
-            })),
-            neuron_id_or_subaccount: None,
-        })?;
-        msgs.push(args);
+        operations.push(NeuronOperation::IncreaseDissolveDelay(
+            additional_dissolve_delay_seconds,
+        ));
+    };
+
+    if let Some(timestamp) = opts.set_dissolve_timestamp {
+        operations.push(NeuronOperation::SetDissolveTimestamp(timestamp));
+    };
+
+    if opts.join_community_fund {
+        operations.push(NeuronOperation::JoinCommunityFund);
     };
 
     if opts.disburse {
-        let args = Encode!(&ManageNeuron {
-            id: id.clone(),
-            command: Some(Command::Disburse(Disburse {
-                to_account: None,
-                amount: None
-            })),
-            neuron_id_or_subaccount: None,
-        })?;
-        msgs.push(args);
+        operations.push(NeuronOperation::Disburse {
+            amount: None,
+            to: None,
+        });
     };
 
     if opts.spawn {
-        let args = Encode!(&ManageNeuron {
-            id: id.clone(),
-            command: Some(Command::Spawn(Default::default())),
-            neuron_id_or_subaccount: None,
-        })?;
-        msgs.push(args);
+        operations.push(NeuronOperation::Spawn);
     };
 
     if let Some(amount) = opts.split {
-        let args = Encode!(&ManageNeuron {
-            id: id.clone(),
-            command: Some(Command::Split(Split {
-                amount_e8s: amount * 100_000_000
-            })),
-            neuron_id_or_subaccount: None,
-        })?;
-        msgs.push(args);
+        operations.push(NeuronOperation::Split(amount));
     };
 
     if let Some(neuron_id) = opts.merge_from_neuron {
-        let args = Encode!(&ManageNeuron {
-            id: id.clone(),
-            command: Some(Command::Merge(Merge {
-                source_neuron_id: Some(NeuronId {
-                    id: parse_neuron_id(neuron_id)
-                }),
-            })),
-            neuron_id_or_subaccount: None,
-        })?;
-        msgs.push(args);
+        operations.push(NeuronOperation::Merge(neuron_id));
     };
 
     if let Some(percentage_to_merge) = opts.merge_maturity {
-        if percentage_to_merge == 0 || percentage_to_merge > 100 {
-            return Err(anyhow!(
-                "Percentage to merge must be a number from 1 to 100"
-            ));
-        }
-        let args = Encode!(&ManageNeuron {
-            id,
-            command: Some(Command::MergeMaturity(MergeMaturity {
-                percentage_to_merge
-            })),
-            neuron_id_or_subaccount: None,
-        })?;
-        msgs.push(args);
+        operations.push(NeuronOperation::MergeMaturity(percentage_to_merge));
+    };
+
+    if let Some(percentage) = opts.stake_maturity {
+        operations.push(NeuronOperation::StakeMaturity(percentage));
+    };
+
+    if opts.enable_auto_stake_maturity && opts.disable_auto_stake_maturity {
+        return Err(anyhow!(
+            "Auto-staking of maturity can't be both enabled and disabled"
+        ));
+    }
+
+    if opts.enable_auto_stake_maturity || opts.disable_auto_stake_maturity {
+        operations.push(NeuronOperation::ChangeAutoStakeMaturity(
+            opts.enable_auto_stake_maturity,
+        ));
+    };
+
+    if let Some(percentage) = opts.disburse_maturity {
+        operations.push(NeuronOperation::DisburseMaturity {
+            percentage,
+            to: opts.disburse_maturity_to.map(|to| to.to_string()),
+        });
+    } else if opts.disburse_maturity_to.is_some() {
+        return Err(anyhow!(
+            "--disburse-maturity-to requires --disburse-maturity"
+        ));
+    };
+
+    if opts.refresh_voting_power {
+        operations.push(NeuronOperation::RefreshVotingPower);
+    };
+
+    if let Some(visibility) = opts.set_visibility {
+        operations.push(NeuronOperation::SetVisibility(visibility));
     };
 
-    if msgs.is_empty() {
+    if operations.is_empty() {
         return Err(anyhow!("No instructions provided"));
     }
 
+    if opts.start_dissolving && opts.stop_dissolving {
+        return Err(anyhow!("Dissolving can't be both started and stopped"));
+    }
+
+    sign_operations(
+        agent,
+        targets
+            .into_iter()
+            .map(|target| (target, operations.clone()))
+            .collect(),
+        opts.force,
+    )
+}
+
+/// Checks the operations of every neuron against the rules of the protocol and signs
+/// them, preserving their order. With `force`, operations failing the checks are signed
+/// anyway.
+pub fn sign_operations(
+    agent: Agent,
+    plan: Vec<(Target, Vec<NeuronOperation>)>,
+    force: bool,
+) -> AnyhowResult<Vec<IngressWithRequestId>> {
+    let (signer, _) = crate::commands::ids::get_ids(&agent)?;
+    let mut findings = Vec::new();
+    for (target, operations) in &plan {
+        for operation in operations {
+            findings.extend(operation.validate(target, signer)?);
+        }
+    }
+    validation::check(findings, force)?;
+
+    let mut msgs = Vec::new();
+    for (target, operations) in plan {
+        for operation in operations {
+            msgs.push(operation.encode(&target)?);
+        }
+    }
+
     let mut generated = Vec::new();
     for args in msgs {
         generated.push(sign_ingress_with_request_status_query(
@@ -210,8 +673,132 @@ pub fn exec(agent: Agent, opts: Opts) -> AnyhowResult<Vec<IngressWithRequestId>>
     Ok(generated)
 }
 
-fn parse_neuron_id(id: String) -> u64 {
+/// Returns the neurons to manage given on the command line or in the neurons file.
+fn parse_targets(agent: &Agent, opts: &Opts) -> AnyhowResult<Vec<Target>> {
+    let mut targets = Vec::new();
+    for neuron_id in &opts.neuron_id {
+        targets.push((
+            Some(NeuronId {
+                id: parse_neuron_id(neuron_id)?,
+            }),
+            None,
+        ));
+    }
+    if let Some(subaccount) = &opts.subaccount {
+        targets.push((
+            None,
+            Some(NeuronIdOrSubaccount::Subaccount(parse_subaccount(
+                subaccount,
+            )?)),
+        ));
+    }
+    match (opts.nonce, &opts.name) {
+        (Some(nonce), _) => targets.push(target_by_nonce(agent, opts.controller, nonce)?),
+        (_, Some(name)) => targets.push(target_by_nonce(
+            agent,
+            opts.controller,
+            convert_name_to_nonce(name),
+        )?),
+        _ => (),
+    }
+    if let Some(path) = &opts.neurons_file {
+        for line in read_from_file(path)?.lines() {
+            let line = line.trim();
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+            targets.push(parse_target(agent, opts.controller, line)?);
+        }
+    }
+    if targets.is_empty() {
+        return Err(anyhow!(
+            "A neuron id, a subaccount, a name, a nonce or a neurons file should be specified"
+        ));
+    }
+    Ok(targets)
+}
+
+/// Parses a neuron given either by its id or as one of `name:<name>`, `nonce:<nonce>`
+/// or `subaccount:<hex>`. Neurons given by name or nonce belong to the controller,
+/// which defaults to the signer.
+pub fn parse_target(
+    agent: &Agent,
+    controller: Option<PrincipalId>,
+    neuron: &str,
+) -> AnyhowResult<Target> {
+    match neuron.split_once(':') {
+        Some(("name", name)) => {
+            neuron_name_validator(name).map_err(|err| anyhow!(err))?;
+            target_by_nonce(agent, controller, convert_name_to_nonce(name))
+        }
+        Some(("nonce", nonce)) => target_by_nonce(
+            agent,
+            controller,
+            nonce
+                .parse()
+                .map_err(|_| anyhow!("Couldn't parse the nonce {}", nonce))?,
+        ),
+        Some(("subaccount", subaccount)) => Ok((
+            None,
+            Some(NeuronIdOrSubaccount::Subaccount(parse_subaccount(
+                subaccount,
+            )?)),
+        )),
+        Some(_) => Err(anyhow!("Couldn't parse the neuron {:?}", neuron)),
+        None => Ok((
+            Some(NeuronId {
+                id: parse_neuron_id(neuron)?,
+            }),
+            None,
+        )),
+    }
+}
+
+/// Returns the neuron with the staking subaccount computed from the controller and the nonce.
+fn target_by_nonce(
+    agent: &Agent,
+    controller: Option<PrincipalId>,
+    nonce: u64,
+) -> AnyhowResult<Target> {
+    let controller = match controller {
+        Some(controller) => controller,
+        None => crate::commands::ids::get_ids(agent)?.0,
+    };
+    let subaccount = compute_neuron_staking_subaccount(controller, nonce);
+    Ok((
+        None,
+        Some(NeuronIdOrSubaccount::Subaccount(subaccount.0.to_vec())),
+    ))
+}
+
+/// Returns the neuron id of the target, or its subaccount in hex.
+fn describe_target(target: &Target) -> String {
+    match target {
+        (Some(id), _) | (_, Some(NeuronIdOrSubaccount::NeuronId(id))) => id.id.to_string(),
+        (_, Some(NeuronIdOrSubaccount::Subaccount(subaccount))) => hex::encode(subaccount),
+        _ => "-".to_string(),
+    }
+}
+
+/// Parses a neuron id, which may be written with `_` separators.
+pub fn parse_neuron_id(id: &str) -> AnyhowResult<u64> {
     id.replace('_', "")
         .parse()
-        .expect("Couldn't parse the neuron id")
+        .map_err(|err| anyhow!("Couldn't parse the neuron id {:?}: {}", id, err))
+}
+
+fn parse_subaccount(subaccount: &str) -> AnyhowResult<Vec<u8>> {
+    let bytes = hex::decode(subaccount.trim_start_matches("0x"))
+        .map_err(|err| anyhow!("Couldn't parse the subaccount: {}", err))?;
+    if bytes.len() != 32 {
+        return Err(anyhow!("The subaccount must be 32 bytes long"));
+    }
+    Ok(bytes)
+}
+
+fn deserialize_principal<'de, D: Deserializer<'de>>(
+    deserializer: D,
+) -> Result<PrincipalId, D::Error> {
+    let text = String::deserialize(deserializer)?;
+    PrincipalId::from_str(&text).map_err(|err| serde::de::Error::custom(format!("{:?}", err)))
 }
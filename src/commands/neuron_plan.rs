@@ -0,0 +1,147 @@
+use crate::{
+    commands::neuron_manage::{parse_target, sign_operations, NeuronOperation, Target},
+    lib::{read_from_file, signing::IngressWithRequestId, AnyhowResult},
+};
+use anyhow::anyhow;
+use clap::Parser;
+use ic_agent::Agent;
+use ic_base_types::PrincipalId;
+use serde::Deserialize;
+
+/// Signs neuron operations listed in a plan file, in the order they are declared.
+///
+/// A plan is a JSON or TOML file listing, for every neuron, the operations to apply, e.g.:
+///
+///     [[neurons]]
+///     neuron = "2313380519530470538"
+///     operations = ["start_dissolving", { add_hot_key = "<principal>" }, { split = 100 }]
+///
+/// Neurons can also be given as `name:<name>`, `nonce:<nonce>` or `subaccount:<hex>`.
+///
+/// Besides the operations of `neuron-manage`, plans can spawn to another controller with
+/// `spawn_to` and disburse to a new neuron with `disburse_to_neuron`, but can't make proposals.
+#[derive(Parser)]
+pub struct Opts {
+    /// Path to the plan file (use "-" for STDIN).
+    file_name: String,
+
+    /// The controller of the neurons addressed by name or nonce, defaults to the signer.
+    #[clap(long)]
+    controller: Option<PrincipalId>,
+
+    /// Sign the operations even if they fail the checks against the rules of the protocol.
+    #[clap(long)]
+    force: bool,
+}
+
+#[derive(Deserialize)]
+struct Plan {
+    neurons: Vec<NeuronPlan>,
+}
+
+#[derive(Deserialize)]
+struct NeuronPlan {
+    neuron: String,
+    operations: Vec<NeuronOperation>,
+}
+
+pub fn exec(agent: Agent, opts: Opts) -> AnyhowResult<Vec<IngressWithRequestId>> {
+    let content = read_from_file(&opts.file_name)?;
+    let plan: Plan = match serde_json::from_str(&content) {
+        Ok(plan) => plan,
+        Err(json_err) => toml::from_str(&content).map_err(|toml_err| {
+            anyhow!(
+                "Couldn't parse the plan as JSON ({}) or as TOML ({})",
+                json_err,
+                toml_err
+            )
+        })?,
+    };
+
+    let mut steps = Vec::new();
+    for neuron in plan.neurons {
+        let target = parse_target(&agent, opts.controller, &neuron.neuron)?;
+        steps.push((neuron.neuron, target, neuron.operations));
+    }
+    validate(&steps)?;
+
+    sign_operations(
+        agent,
+        steps
+            .into_iter()
+            .map(|(_, target, operations)| (target, operations))
+            .collect(),
+        opts.force,
+    )
+}
+
+/// Checks the plan as a whole: fails on steps that can't succeed and warns about
+/// steps that are most likely a mistake.
+fn validate(steps: &[(String, Target, Vec<NeuronOperation>)]) -> AnyhowResult {
+    if steps.is_empty() {
+        return Err(anyhow!("The plan doesn't list any neurons"));
+    }
+    for (index, (name, target, operations)) in steps.iter().enumerate() {
+        if operations.is_empty() {
+            return Err(anyhow!("Neuron {}: no operations listed", name));
+        }
+        if steps[..index].iter().any(|(_, other, _)| other == target) {
+            eprintln!(
+                "Warning: neuron {} is listed more than once, its operations will be signed in separate groups",
+                name
+            );
+        }
+        let mut disbursed = false;
+        for (position, operation) in operations.iter().enumerate() {
+            if let NeuronOperation::MakeProposal(_) = operation {
+                return Err(anyhow!(
+                    "Neuron {}: make_proposal isn't supported in plans, proposals must be signed on their own",
+                    name
+                ));
+            }
+            if disbursed {
+                eprintln!(
+                    "Warning: neuron {}: {} follows a disburse of the entire stake",
+                    name,
+                    operation.name()
+                );
+            }
+            if let NeuronOperation::Disburse { amount: None, .. } = operation {
+                disbursed = true;
+            }
+            if position > 0 {
+                let previous = &operations[position - 1];
+                if previous.name() == operation.name()
+                    && !matches!(
+                        operation,
+                        NeuronOperation::AddHotKey(_)
+                            | NeuronOperation::RemoveHotKey(_)
+                            | NeuronOperation::Follow { .. }
+                            | NeuronOperation::RegisterVote { .. }
+                            | NeuronOperation::Split(_)
+                    )
+                {
+                    eprintln!("Warning: neuron {}: {} is repeated", name, operation.name());
+                }
+                if matches!(
+                    (previous, operation),
+                    (
+                        NeuronOperation::StartDissolving,
+                        NeuronOperation::StopDissolving
+                    ) | (
+                        NeuronOperation::StopDissolving,
+                        NeuronOperation::StartDissolving
+                    )
+                ) {
+                    eprintln!(
+                        "Warning: neuron {}: {} undoes the preceding {}",
+                        name,
+                        operation.name(),
+                        previous.name()
+                    );
+                }
+            }
+        }
+    }
+    Ok(())
+}
@@ -1,8 +1,9 @@
 use crate::{
-    commands::transfer,
+    commands::transfer::{self, parse_icpts},
     lib::{
         governance_canister_id,
         signing::{sign_ingress_with_request_status_query, IngressWithRequestId},
+        validation::{self, Finding, MIN_STAKE_E8S},
         AnyhowResult,
     },
 };
@@ -36,6 +37,11 @@ pub struct Opts {
     /// Transaction fee, default is 10000 e8s.
     #[clap(long)]
     fee: Option<String>,
+
+    /// Sign the transfer even if it fails the checks against the rules of the protocol,
+    /// e.g. to top up an existing neuron with less than 1 ICP.
+    #[clap(long)]
+    force: bool,
 }
 
 pub fn exec(agent: Agent, opts: Opts) -> AnyhowResult<Vec<IngressWithRequestId>> {
@@ -49,6 +55,18 @@ pub fn exec(agent: Agent, opts: Opts) -> AnyhowResult<Vec<IngressWithRequestId>>
         PrincipalId::from_str(&controller.to_string()).expect("couldn't parse principal id"),
         nonce,
     );
+    if let Some(amount) = &opts.amount {
+        let e8s = parse_icpts(amount)
+            .map_err(|err| anyhow!("Couldn't parse the amount: {}", err))?
+            .get_e8s();
+        let mut findings = Vec::new();
+        if e8s < MIN_STAKE_E8S {
+            findings.push(Finding::Error(
+                "The amount is less than the minimum stake of 1 ICP, a new neuron wouldn't be created".to_string(),
+            ));
+        }
+        validation::check(findings, opts.force)?;
+    }
     let mut messages = match opts.amount {
         Some(amount) => transfer::exec(
             agent.clone(),
@@ -76,7 +94,7 @@ pub fn exec(agent: Agent, opts: Opts) -> AnyhowResult<Vec<IngressWithRequestId>>
     Ok(messages)
 }
 
-fn convert_name_to_nonce(name: &str) -> u64 {
+pub fn convert_name_to_nonce(name: &str) -> u64 {
     let mut bytes = std::collections::VecDeque::from(name.as_bytes().to_vec());
     while bytes.len() < 8 {
         bytes.push_front(0)
@@ -86,7 +104,7 @@ fn convert_name_to_nonce(name: &str) -> u64 {
     u64::from_be_bytes(arr)
 }
 
-fn neuron_name_validator(name: &str) -> Result<(), String> {
+pub fn neuron_name_validator(name: &str) -> Result<(), String> {
     // Convert to bytes before checking the length to restrict it to ASCII only
     if name.as_bytes().len() > 8 {
         return Err("The neuron name must be 8 character or less".to_string());
@@ -1,10 +1,30 @@
 use crate::lib::{
-    get_idl_string, read_from_file, request_status, send_ingress,
-    signing::{Ingress, IngressWithRequestId},
-    AnyhowResult, IngressResult,
+    candid_json::get_idl_json,
+    ckbtc,
+    duration::{format_duration, format_timestamp, now_seconds, parse_duration},
+    envelope::{verify_ingress, verify_ingress_with_request_id, Envelope},
+    get_idl_string, get_method_type,
+    governance::{describe_manage_neuron, describe_update_reply, render_reply, ManageNeuronTarget},
+    governance_canister_id, icrc1,
+    journal::Journal,
+    neuron_report::{self, ReportFormat},
+    read_from_file,
+    request_status::{self, OutcomeUnknown},
+    send_ingress,
+    signing::{
+        Ingress, IngressWithRequestId, RequestStatus, MAX_INGRESS_EXPIRY_SECONDS,
+        PERMITTED_DRIFT_SECONDS,
+    },
+    sns, AnyhowResult, IngressResult,
 };
 use anyhow::anyhow;
+use candid::{Decode, IDLArgs, Principal};
 use clap::Parser;
+use ic_agent::{agent::RequestStatusResponse, AgentError};
+use ic_nns_governance::pb::v1::manage_neuron::NeuronIdOrSubaccount;
+use serde::Serialize;
+use std::collections::HashSet;
+use std::time::Duration;
 
 /// Sends a signed message or a set of messages.
 #[derive(Parser)]
@@ -23,81 +43,1025 @@ pub struct Opts {
     /// Print raw output
     #[clap(long)]
     raw: bool,
+
+    /// Format of the neuron report rendered from `list-neurons` responses: table, csv or json.
+    /// With csv and json, only the report is printed on stdout; the message is shown on stderr.
+    #[clap(long, default_value = "table")]
+    report_format: ReportFormat,
+
+    /// Also display the expiry, the nonce and the request id of every message, and the
+    /// paths read by its request status query. They change every time a message is signed,
+    /// so they are left out by default to keep the output comparable across signings.
+    #[clap(long)]
+    show_envelope: bool,
+
+    /// Print one JSON record per message and per line instead of text, for scripts. Every
+    /// record has the canister id, the method name, the call type, the request id, the
+    /// arguments decoded as JSON, the status (not_sent, skipped, submitted, replied,
+    /// rejected, error or unknown), the reply decoded as JSON and as hex, the reject code
+    /// and the error.
+    #[clap(long, conflicts_with("raw"))]
+    json: bool,
+
+    /// Don't send anything: only fetch the outcome of messages sent before, e.g. when the
+    /// connection was lost while waiting for it, and report the final state of every message.
+    #[clap(long, conflicts_with("dry-run"))]
+    status_only: bool,
+
+    /// Send only these messages of the bundle, by their position starting from 1, e.g. "2"
+    /// or "1,3-4". The other messages are skipped.
+    #[clap(long)]
+    select: Option<String>,
+
+    /// The journal of the messages sent from this machine, by default
+    /// `~/.quill/journal.jsonl`.
+    #[clap(long)]
+    journal: Option<String>,
+
+    /// Send messages even if the journal shows they were already sent.
+    #[clap(long)]
+    resend: bool,
+
+    /// How long to wait for the outcome of every message (e.g. "2m"). By default, waits for
+    /// as long as its request status queries are valid.
+    #[clap(long)]
+    timeout: Option<String>,
 }
 
 pub async fn exec(opts: Opts) -> AnyhowResult {
+    let timeout = opts
+        .timeout
+        .as_deref()
+        .map(parse_duration)
+        .transpose()?
+        .map(Duration::from_secs);
     let json = read_from_file(&opts.file_name)?;
+    if opts.status_only {
+        return exec_status_only(&json, timeout, &opts).await;
+    }
+    if opts.json {
+        return exec_json(&json, timeout, &opts).await;
+    }
     if let Ok(val) = serde_json::from_str::<Ingress>(&json) {
-        send(&val, &opts).await?;
+        verify_ingress(&val).map_err(|err| anyhow!("Invalid message: {}", err))?;
+        let selection = selection(&opts, 1)?;
+        check_journal(
+            &[copy_request_ids(&val, &val.later_copies, |msg| {
+                &msg.content
+            })?],
+            &selection,
+            &opts,
+        )?;
+        if selection.contains(&0) {
+            let copy = select_copy(&val, &val.later_copies, |msg| &msg.content, &opts)?;
+            send(copy, &[], &opts).await?;
+        }
     } else if let Ok(vals) = serde_json::from_str::<Vec<Ingress>>(&json) {
-        for msg in vals {
-            send(&msg, &opts).await?;
+        verify_all(&vals, verify_ingress)?;
+        let selection = selection(&opts, vals.len())?;
+        check_journal(
+            &vals
+                .iter()
+                .map(|msg| copy_request_ids(msg, &msg.later_copies, |msg| &msg.content))
+                .collect::<AnyhowResult<Vec<_>>>()?,
+            &selection,
+            &opts,
+        )?;
+        let mut dispositions = vec![Disposition::Pending; vals.len()];
+        for (index, msg) in vals.iter().enumerate() {
+            if !selection.contains(&index) {
+                dispositions[index] = Disposition::Skipped;
+                continue;
+            }
+            let copy = select_copy(msg, &msg.later_copies, |msg| &msg.content, &opts)?;
+            dispositions[index] = send(copy, &[], &opts).await?;
+            if dispositions[index] == Disposition::Pending {
+                break;
+            }
         }
+        print_summary(&dispositions, &opts);
     } else if let Ok(vals) = serde_json::from_str::<Vec<IngressWithRequestId>>(&json) {
-        for tx in vals {
-            submit_ingress_and_check_status(&tx, &opts).await?;
+        verify_all(&vals, verify_ingress_with_request_id)?;
+        let selection = selection(&opts, vals.len())?;
+        check_journal(
+            &vals
+                .iter()
+                .map(|msg| copy_request_ids(msg, &msg.later_copies, |msg| &msg.ingress.content))
+                .collect::<AnyhowResult<Vec<_>>>()?,
+            &selection,
+            &opts,
+        )?;
+        let mut dispositions = vec![Disposition::Pending; vals.len()];
+        let mut outcomes = Vec::new();
+        for (index, msg) in vals.iter().enumerate() {
+            if !selection.contains(&index) {
+                dispositions[index] = Disposition::Skipped;
+                continue;
+            }
+            let tx =
+                select_copy(msg, &msg.later_copies, |msg| &msg.ingress.content, &opts)?.clone();
+            dispositions[index] = send(&tx.ingress, &tx.status_queries(), &opts).await?;
+            match dispositions[index] {
+                Disposition::Sent if !opts.dry_run => (),
+                Disposition::Pending => break,
+                _ => continue,
+            }
+            let outcome = match check_status(&tx, timeout, &opts).await {
+                Err(err) if err.is::<OutcomeUnknown>() && index + 1 < vals.len() => {
+                    print_summary(&dispositions, &opts);
+                    return Err(anyhow!(OutcomeUnknown(format!(
+                        "{}\nThe remaining messages weren't sent",
+                        err
+                    ))));
+                }
+                result => result?,
+            };
+            outcomes.push((tx, Some(outcome)));
+        }
+        if !opts.dry_run && !opts.raw {
+            print_neuron_outcomes(&outcomes)?;
+        }
+        print_summary(&dispositions, &opts);
+        let failures = outcomes
+            .iter()
+            .filter(|(_, outcome)| matches!(outcome, Some(Err(_))))
+            .count();
+        if failures > 0 {
+            return Err(anyhow!(
+                "{} of {} messages failed",
+                failures,
+                outcomes.len()
+            ));
+        }
+    } else {
+        return Err(anyhow!("Invalid JSON content"));
+    }
+    Ok(())
+}
+
+/// What became of a message of a bundle.
+#[derive(Clone, Copy, PartialEq)]
+enum Disposition {
+    Sent,
+    /// Skipped when asked for confirmation, or not selected with `--select`.
+    Skipped,
+    /// Not sent because sending was stopped at or before this message.
+    Pending,
+}
+
+/// Returns the positions, starting from 0, of the messages selected with `--select`, by
+/// default all of them.
+fn selection(opts: &Opts, count: usize) -> AnyhowResult<HashSet<usize>> {
+    let input = match &opts.select {
+        Some(input) => input,
+        None => return Ok((0..count).collect()),
+    };
+    let parse = |number: &str| {
+        number
+            .trim()
+            .parse::<usize>()
+            .map_err(|_| anyhow!("Invalid message selection: {}", input))
+    };
+    let mut selection = HashSet::new();
+    for part in input.split(',') {
+        let (first, last) = match part.split_once('-') {
+            Some((first, last)) => (parse(first)?, parse(last)?),
+            None => (parse(part)?, parse(part)?),
+        };
+        if first == 0 || first > last || last > count {
+            return Err(anyhow!(
+                "Invalid message selection: {}, the messages are numbered from 1 to {}",
+                part.trim(),
+                count
+            ));
+        }
+        selection.extend(first - 1..last);
+    }
+    Ok(selection)
+}
+
+/// Asks whether to send the message: returns `Sent` to send it, `Skipped` to skip it and
+/// `Pending` to stop sending.
+fn confirm(question: &str, opts: &Opts) -> AnyhowResult<Disposition> {
+    let prompt = format!(
+        "{} [y/s/N] (y: send it, s: skip it, N: stop sending)",
+        question
+    );
+    if opts.json || opts.report_format != ReportFormat::Table {
+        eprintln!("{}", prompt);
+    } else {
+        println!("\n{}", prompt);
+    }
+    let mut input = String::new();
+    std::io::stdin().read_line(&mut input)?;
+    Ok(match input.to_lowercase().trim() {
+        "y" | "yes" => Disposition::Sent,
+        "s" | "skip" => Disposition::Skipped,
+        _ => Disposition::Pending,
+    })
+}
+
+/// Prints which messages of the bundle were sent, skipped or not sent, so that a bundle
+/// sent only in part doesn't go unnoticed.
+fn print_summary(dispositions: &[Disposition], opts: &Opts) {
+    if opts.dry_run
+        || opts.raw
+        || opts.report_format != ReportFormat::Table
+        || dispositions.len() < 2
+    {
+        return;
+    }
+    println!();
+    for (disposition, label) in &[
+        (Disposition::Sent, "Sent:"),
+        (Disposition::Skipped, "Skipped:"),
+        (Disposition::Pending, "Not sent:"),
+    ] {
+        let positions = dispositions
+            .iter()
+            .enumerate()
+            .filter(|(_, other)| *other == disposition)
+            .map(|(index, _)| (index + 1).to_string())
+            .collect::<Vec<_>>();
+        if !positions.is_empty() {
+            println!("{:9} {}", label, positions.join(", "));
+        }
+    }
+}
+
+/// The outcome of a message, printed as a line of JSON with `--json`.
+#[derive(Serialize)]
+struct Record {
+    canister_id: String,
+    method_name: String,
+    call_type: String,
+    request_id: Option<String>,
+    arguments: Option<serde_json::Value>,
+    status: &'static str,
+    reply: Option<serde_json::Value>,
+    reply_hex: Option<String>,
+    reject_code: Option<u64>,
+    error: Option<String>,
+}
+
+/// Sends the messages like `exec`, but prints a JSON record for every message instead of
+/// text. Fails if a message was rejected or failed, with `OutcomeUnknown` if the outcome of
+/// a message is unknown.
+async fn exec_json(json: &str, timeout: Option<Duration>, opts: &Opts) -> AnyhowResult {
+    let messages: Vec<(Ingress, Vec<RequestStatus>, bool)> = if let Ok(val) =
+        serde_json::from_str::<Ingress>(json)
+    {
+        verify_ingress(&val).map_err(|err| anyhow!("Invalid message: {}", err))?;
+        let selection = selection(opts, 1)?;
+        check_journal(
+            &[copy_request_ids(&val, &val.later_copies, |msg| {
+                &msg.content
+            })?],
+            &selection,
+            opts,
+        )?;
+        if selection.contains(&0) {
+            let copy = select_copy(&val, &val.later_copies, |msg| &msg.content, opts)?;
+            vec![(copy.clone(), Vec::new(), true)]
+        } else {
+            vec![(val, Vec::new(), false)]
         }
+    } else if let Ok(vals) = serde_json::from_str::<Vec<Ingress>>(json) {
+        verify_all(&vals, verify_ingress)?;
+        let selection = selection(opts, vals.len())?;
+        check_journal(
+            &vals
+                .iter()
+                .map(|msg| copy_request_ids(msg, &msg.later_copies, |msg| &msg.content))
+                .collect::<AnyhowResult<Vec<_>>>()?,
+            &selection,
+            opts,
+        )?;
+        vals.iter()
+            .enumerate()
+            .map(|(index, msg)| {
+                if !selection.contains(&index) {
+                    return Ok((msg.clone(), Vec::new(), false));
+                }
+                let copy = select_copy(msg, &msg.later_copies, |msg| &msg.content, opts)?;
+                Ok((copy.clone(), Vec::new(), true))
+            })
+            .collect::<AnyhowResult<_>>()?
+    } else if let Ok(vals) = serde_json::from_str::<Vec<IngressWithRequestId>>(json) {
+        verify_all(&vals, verify_ingress_with_request_id)?;
+        let selection = selection(opts, vals.len())?;
+        check_journal(
+            &vals
+                .iter()
+                .map(|msg| copy_request_ids(msg, &msg.later_copies, |msg| &msg.ingress.content))
+                .collect::<AnyhowResult<Vec<_>>>()?,
+            &selection,
+            opts,
+        )?;
+        vals.iter()
+            .enumerate()
+            .map(|(index, msg)| {
+                if !selection.contains(&index) {
+                    return Ok((msg.ingress.clone(), Vec::new(), false));
+                }
+                let copy = select_copy(msg, &msg.later_copies, |msg| &msg.ingress.content, opts)?;
+                Ok((copy.ingress.clone(), copy.status_queries(), true))
+            })
+            .collect::<AnyhowResult<_>>()?
     } else {
         return Err(anyhow!("Invalid JSON content"));
+    };
+    let mut failures = 0;
+    let mut stopped = false;
+    for (index, (message, statuses, selected)) in messages.iter().enumerate() {
+        let record = if stopped || !selected {
+            let mut record = new_record(message)?;
+            if !selected {
+                record.status = "skipped";
+            }
+            record
+        } else {
+            send_json(message, statuses, timeout, opts).await?
+        };
+        println!("{}", serde_json::to_string(&record)?);
+        match record.status {
+            // Outside a dry run, a message is only left unsent when sending was stopped.
+            "not_sent" if !opts.dry_run => stopped = true,
+            "unknown" => {
+                return Err(anyhow!(OutcomeUnknown(format!(
+                    "The outcome of message {} is unknown, the remaining {} messages weren't sent",
+                    index + 1,
+                    messages.len() - index - 1
+                ))))
+            }
+            "rejected" | "error" => failures += 1,
+            _ => (),
+        }
+    }
+    if failures > 0 {
+        return Err(anyhow!(
+            "{} of {} messages failed",
+            failures,
+            messages.len()
+        ));
     }
     Ok(())
 }
 
-async fn submit_ingress_and_check_status(
+/// Sends the message, waits for its outcome if it has request status queries, and returns
+/// the record of the outcome.
+async fn send_json(
+    message: &Ingress,
+    statuses: &[RequestStatus],
+    timeout: Option<Duration>,
+    opts: &Opts,
+) -> AnyhowResult<Record> {
+    let mut record = new_record(message)?;
+    if opts.dry_run {
+        return Ok(record);
+    }
+    if message.call_type == "update" && !opts.yes {
+        let question = format!(
+            "Do you want to send the call to {} of {}?",
+            record.method_name, record.canister_id
+        );
+        match confirm(&question, opts)? {
+            Disposition::Sent => (),
+            Disposition::Skipped => {
+                record.status = "skipped";
+                return Ok(record);
+            }
+            Disposition::Pending => return Ok(record),
+        }
+    }
+    record_sending(message, opts)?;
+    let reply = match send_ingress(message).await {
+        Ok(IngressResult::QueryResponse(reply)) => Ok(reply),
+        Ok(IngressResult::RequestId(_)) if statuses.is_empty() => {
+            record_in_journal(message, "submitted", opts);
+            record.status = "submitted";
+            return Ok(record);
+        }
+        Ok(IngressResult::RequestId(_)) => {
+            record_in_journal(message, "submitted", opts);
+            request_status::submit(statuses, true, timeout).await
+        }
+        Err(err) => Err(err),
+    };
+    record_outcome(&mut record, &reply)?;
+    if message.call_type == "update" {
+        record_in_journal(message, record.status, opts);
+    }
+    Ok(record)
+}
+
+/// Returns the record of a message that wasn't sent.
+fn new_record(message: &Ingress) -> AnyhowResult<Record> {
+    let (_, canister_id, method_name, arg) = message.parse_raw()?;
+    let request_id = hex::encode(Envelope::decode(&message.content)?.request_id()?);
+    Ok(Record {
+        canister_id: canister_id.to_text(),
+        method_name: method_name.clone(),
+        call_type: message.call_type.clone(),
+        request_id: if message.call_type == "update" {
+            Some(request_id)
+        } else {
+            None
+        },
+        arguments: get_idl_json(&arg, canister_id, &method_name, "args").ok(),
+        status: "not_sent",
+        reply: None,
+        reply_hex: None,
+        reject_code: None,
+        error: None,
+    })
+}
+
+/// Records the reply of the message, or why there is none.
+fn record_outcome(record: &mut Record, reply: &AnyhowResult<Vec<u8>>) -> AnyhowResult {
+    let canister_id = Principal::from_text(&record.canister_id)?;
+    record.status = outcome_state(reply, canister_id, &record.method_name);
+    match reply {
+        Ok(reply) => {
+            if let Some(Err(error)) = describe_reply(canister_id, &record.method_name, reply) {
+                record.error = Some(error);
+            }
+            record.reply = get_idl_json(reply, canister_id, &record.method_name, "rets").ok();
+            record.reply_hex = Some(hex::encode(reply));
+        }
+        Err(err) => {
+            if let Some(AgentError::ReplicaError { reject_code, .. }) = err.downcast_ref() {
+                record.reject_code = Some(*reject_code);
+            }
+            record.error = Some(err.to_string());
+        }
+    }
+    Ok(())
+}
+
+/// Returns the state of a message from its reply: replied, error (the reply or the call
+/// failed), rejected or unknown.
+fn outcome_state(
+    reply: &AnyhowResult<Vec<u8>>,
+    canister_id: Principal,
+    method_name: &str,
+) -> &'static str {
+    match reply {
+        Ok(reply) => match describe_reply(canister_id, method_name, reply) {
+            Some(Err(_)) => "error",
+            _ => "replied",
+        },
+        Err(err) if err.is::<OutcomeUnknown>() => "unknown",
+        Err(err) => match err.downcast_ref() {
+            Some(AgentError::ReplicaError { .. }) => "rejected",
+            _ => "error",
+        },
+    }
+}
+
+/// Fetches the outcome of every message of a bundle sent before, without sending anything,
+/// and prints the final state of every message: replied, error, rejected, unknown or expired.
+async fn exec_status_only(json: &str, timeout: Option<Duration>, opts: &Opts) -> AnyhowResult {
+    let vals = serde_json::from_str::<Vec<IngressWithRequestId>>(json).map_err(|_| {
+        anyhow!(
+            "Only messages signed with request status queries can be checked with --status-only"
+        )
+    })?;
+    verify_all(&vals, verify_ingress_with_request_id)?;
+    let mut states = Vec::new();
+    for (index, message) in vals.iter().enumerate() {
+        let (copy, reply) = fetch_outcome(message, timeout, opts.json || opts.raw).await;
+        let mut record = new_record(&copy.ingress)?;
+        match &reply {
+            Some(reply) => record_outcome(&mut record, reply)?,
+            None => {
+                record.status = "expired";
+                record.error = Some(
+                    "All request status queries have expired, the outcome can't be checked anymore"
+                        .to_string(),
+                );
+            }
+        }
+        if reply.is_some() && record.status != "unknown" {
+            record_in_journal(&copy.ingress, record.status, opts);
+        }
+        if opts.json {
+            println!("{}", serde_json::to_string(&record)?);
+        } else {
+            println!(
+                "Message {}: {} of {} (request 0x{})\n",
+                index + 1,
+                record.method_name,
+                record.canister_id,
+                record.request_id.as_deref().unwrap_or_default()
+            );
+            let canister_id = Principal::from_text(&record.canister_id)?;
+            match reply {
+                Some(Err(err)) if err.is::<OutcomeUnknown>() => println!("{}\n", err),
+                Some(reply) => {
+                    print_outcome(reply, canister_id, &record.method_name, opts)?;
+                }
+                None => println!("{}\n", record.error.as_deref().unwrap_or_default()),
+            }
+        }
+        states.push(record.status);
+    }
+    if !opts.json {
+        for (index, state) in states.iter().enumerate() {
+            println!("Message {}: {}", index + 1, state);
+        }
+    }
+    let count = |matching: &[&str]| {
+        states
+            .iter()
+            .filter(|state| matching.contains(*state))
+            .count()
+    };
+    let unknown = count(&["unknown", "expired"]);
+    if unknown > 0 {
+        return Err(anyhow!(OutcomeUnknown(format!(
+            "The outcome of {} of {} messages is unknown",
+            unknown,
+            states.len()
+        ))));
+    }
+    let failures = count(&["error", "rejected"]);
+    if failures > 0 {
+        return Err(anyhow!("{} of {} messages failed", failures, states.len()));
+    }
+    Ok(())
+}
+
+/// Fetches the outcome of a message sent before, without sending it again: reads the status
+/// of every copy of the message and polls the one the IC received until it has an outcome.
+/// Returns that copy with its outcome, or `None` if the status queries of every copy have
+/// expired.
+async fn fetch_outcome(
     message: &IngressWithRequestId,
+    timeout: Option<Duration>,
+    silent: bool,
+) -> (&IngressWithRequestId, Option<AnyhowResult<Vec<u8>>>) {
+    let mut readable = false;
+    for copy in std::iter::once(message).chain(&message.later_copies) {
+        let statuses = copy.status_queries();
+        match request_status::read(&statuses).await {
+            Ok(None) => (),
+            Ok(Some(RequestStatusResponse::Unknown)) => readable = true,
+            Ok(Some(_)) => {
+                return (
+                    copy,
+                    Some(request_status::submit(&statuses, silent, timeout).await),
+                )
+            }
+            Err(err) => return (copy, Some(Err(err))),
+        }
+    }
+    let reply = if readable {
+        Some(Err(anyhow!(OutcomeUnknown(
+            "The IC has no record of the message: it wasn't sent, or its status was dropped"
+                .to_string()
+        ))))
+    } else {
+        None
+    };
+    (message, reply)
+}
+
+/// Returns the copy of the message that the IC accepts now: one that hasn't expired and
+/// doesn't expire further in the future than allowed. If there is none, a dry run shows
+/// the first copy with a warning.
+fn select_copy<'a, T>(
+    message: &'a T,
+    later_copies: &'a [T],
+    content: impl Fn(&T) -> &str,
+    opts: &Opts,
+) -> AnyhowResult<&'a T> {
+    let now = now_seconds();
+    let mut expiries = Vec::new();
+    for copy in std::iter::once(message).chain(later_copies) {
+        let expiry = Envelope::decode(content(copy))?
+            .ingress_expiry()
+            .unwrap_or_default()
+            / 1_000_000_000;
+        if expiry > now && expiry <= now + MAX_INGRESS_EXPIRY_SECONDS + PERMITTED_DRIFT_SECONDS {
+            return Ok(copy);
+        }
+        expiries.push(expiry);
+    }
+    let problem = match expiries.iter().find(|expiry| **expiry > now) {
+        Some(expiry) => {
+            let valid_from = expiry - MAX_INGRESS_EXPIRY_SECONDS - PERMITTED_DRIFT_SECONDS;
+            format!(
+                "The message can only be sent from {} (in {}), the clock of the signing machine may be ahead",
+                format_timestamp(valid_from),
+                format_duration(valid_from - now)
+            )
+        }
+        None => {
+            let expiry = expiries.iter().max().copied().unwrap_or_default();
+            format!(
+                "The message expired at {} ({} ago), it must be signed again",
+                format_timestamp(expiry),
+                format_duration(now - expiry)
+            )
+        }
+    };
+    if !opts.dry_run {
+        return Err(anyhow!(problem));
+    }
+    eprintln!("Warning: {}", problem);
+    Ok(message)
+}
+
+/// Returns the request ids of the message and of its later copies, as hex.
+fn copy_request_ids<T>(
+    message: &T,
+    later_copies: &[T],
+    content: impl Fn(&T) -> &str,
+) -> AnyhowResult<Vec<String>> {
+    std::iter::once(message)
+        .chain(later_copies)
+        .map(|copy| Ok(hex::encode(Envelope::decode(content(copy))?.request_id()?)))
+        .collect()
+}
+
+/// Refuses the bundle if the journal shows that one of its selected messages was already
+/// sent, in any of its copies, unless `--resend` is given. A dry run only warns.
+fn check_journal(
+    request_ids: &[Vec<String>],
+    selection: &HashSet<usize>,
     opts: &Opts,
 ) -> AnyhowResult {
-    send(&message.ingress, opts).await?;
-    if opts.dry_run {
+    let request_ids = request_ids
+        .iter()
+        .enumerate()
+        .filter(|(index, _)| selection.contains(index))
+        .collect::<Vec<_>>();
+    if request_ids.is_empty() {
         return Ok(());
     }
+    let journal = match Journal::open(opts.journal.as_deref()) {
+        Ok(journal) => journal,
+        // Nothing is sent in a dry run, so it doesn't need a journal.
+        Err(_) if opts.dry_run => return Ok(()),
+        Err(err) => return Err(err),
+    };
+    let entries = journal.find(
+        &request_ids
+            .iter()
+            .flat_map(|(_, ids)| ids.iter().cloned())
+            .collect(),
+    )?;
+    let mut sent = Vec::new();
+    for (index, ids) in request_ids {
+        if let Some(entry) = entries
+            .iter()
+            .filter(|entry| ids.contains(&entry.request_id))
+            .last()
+        {
+            sent.push(format!(
+                "Message {}: {} of {} was sent at {} (last known state: {})",
+                index + 1,
+                entry.method_name,
+                entry.canister_id,
+                format_timestamp(entry.time),
+                entry.state
+            ));
+        }
+    }
+    if sent.is_empty() {
+        return Ok(());
+    }
+    if opts.resend || opts.dry_run {
+        for message in sent {
+            eprintln!("Warning: {}", message);
+        }
+        return Ok(());
+    }
+    Err(anyhow!(
+        "Some messages were already sent, nothing was sent:\n  {}\nUse --resend to send them again.",
+        sent.join("\n  ")
+    ))
+}
+
+/// Appends an entry about the message, keyed by its request id, to the journal.
+fn append_to_journal(message: &Ingress, state: &str, opts: &Opts) -> AnyhowResult {
+    let (_, canister_id, method_name, _) = message.parse_raw()?;
+    let request_id = hex::encode(Envelope::decode(&message.content)?.request_id()?);
+    Journal::open(opts.journal.as_deref())?.append(
+        &request_id,
+        &canister_id.to_text(),
+        &method_name,
+        state,
+    )
+}
+
+/// Records in the journal that the message is about to be sent, so that it isn't sent
+/// again even if sending fails or is interrupted after it reached the IC. Nothing is sent
+/// if the journal can't be written.
+fn record_sending(message: &Ingress, opts: &Opts) -> AnyhowResult {
+    if message.call_type != "update" {
+        return Ok(());
+    }
+    append_to_journal(message, "sending", opts).map_err(|err| {
+        anyhow!(
+            "Couldn't record the message in the journal, it wasn't sent: {}",
+            err
+        )
+    })
+}
+
+/// Appends an entry about the message to the journal. The message is already sent, so
+/// failing to write the journal is only a warning.
+fn record_in_journal(message: &Ingress, state: &str, opts: &Opts) {
+    if let Err(err) = append_to_journal(message, state, opts) {
+        eprintln!(
+            "Warning: couldn't record the message in the journal: {}",
+            err
+        );
+    }
+}
+
+/// Verifies every message of the bundle before any of them is shown or sent, and refuses
+/// the whole bundle if one is invalid.
+fn verify_all<T, R>(messages: &[T], verify: impl Fn(&T) -> AnyhowResult<R>) -> AnyhowResult {
+    let errors = messages
+        .iter()
+        .enumerate()
+        .filter_map(|(index, message)| {
+            verify(message)
+                .err()
+                .map(|err| format!("Message {}: {}", index + 1, err))
+        })
+        .collect::<Vec<_>>();
+    if errors.is_empty() {
+        return Ok(());
+    }
+    Err(anyhow!(
+        "The bundle is invalid, nothing was sent:\n  {}",
+        errors.join("\n  ")
+    ))
+}
+
+/// Waits for the reply of a sent message. Returns the outcome of the message:
+/// `Ok` with a summary if it succeeded or `Err` with an explanation if it was rejected
+/// or the canister replied with an error.
+async fn check_status(
+    message: &IngressWithRequestId,
+    timeout: Option<Duration>,
+    opts: &Opts,
+) -> AnyhowResult<Result<String, String>> {
     let (_, canister_id, method_name, _) = &message.ingress.parse()?;
     let silent = opts.raw;
-    match request_status::submit(&message.request_status, silent).await {
+    let reply = request_status::submit(&message.status_queries(), silent, timeout).await;
+    record_in_journal(
+        &message.ingress,
+        outcome_state(&reply, *canister_id, method_name),
+        opts,
+    );
+    print_outcome(reply, *canister_id, method_name, opts)
+}
+
+/// Prints the reply of a message, or why there is none, and returns a short description
+/// of its outcome.
+fn print_outcome(
+    reply: AnyhowResult<Vec<u8>>,
+    canister_id: Principal,
+    method_name: &str,
+    opts: &Opts,
+) -> AnyhowResult<Result<String, String>> {
+    let outcome = match reply {
         Ok(blob) if opts.raw => {
             use std::io::Write;
             let mut out = std::io::stdout();
             out.write_all(&blob)?;
             out.flush()?;
+            describe_outcome(canister_id, method_name, &blob)
         }
         Ok(blob) => {
-            let response = crate::lib::get_idl_string(&blob, *canister_id, method_name, "rets");
-            println!("{}\n", response.map_err(|e| anyhow!(e))?)
+            let response = crate::lib::get_idl_string(&blob, canister_id, method_name, "rets");
+            println!("{}\n", response.map_err(|e| anyhow!(e))?);
+            let outcome = describe_outcome(canister_id, method_name, &blob);
+            match &outcome {
+                Err(error) => println!("{}\n", error),
+                Ok(summary) if summary != "OK" => println!("{}\n", summary),
+                _ => (),
+            }
+            outcome
+        }
+        Err(err) if err.is::<OutcomeUnknown>() => return Err(err),
+        Err(err) => {
+            println!("{}\n", err);
+            Err(err.to_string())
         }
-        Err(err) => println!("{}\n", err),
     };
+    Ok(outcome)
+}
+
+/// Describes the outcome of a reply: as the canisters we know the replies of describe it,
+/// as "OK" for other replies, or with the raw reply if it doesn't match the candid of the
+/// method, since nothing can then be said about the outcome.
+fn describe_outcome(
+    canister_id: Principal,
+    method_name: &str,
+    blob: &[u8],
+) -> Result<String, String> {
+    if let Some(outcome) = describe_reply(canister_id, method_name, blob) {
+        return outcome;
+    }
+    let decoded = match get_method_type(canister_id, method_name) {
+        Some((env, func)) => IDLArgs::from_bytes_with_types(blob, &env, &func.rets).is_ok(),
+        None => IDLArgs::from_bytes(blob).is_ok(),
+    };
+    if decoded {
+        Ok("OK".to_string())
+    } else {
+        Ok(format!(
+            "Unknown outcome, the reply couldn't be decoded: 0x{}",
+            hex::encode(blob)
+        ))
+    }
+}
+
+/// Describes the outcome of an update call to one of the canisters we know the replies of.
+fn describe_reply(
+    canister_id: Principal,
+    method_name: &str,
+    blob: &[u8],
+) -> Option<Result<String, String>> {
+    describe_update_reply(canister_id, method_name, blob)
+        .or_else(|| sns::describe_update_reply(canister_id, method_name, blob))
+        .or_else(|| icrc1::describe_update_reply(method_name, blob))
+        .or_else(|| ckbtc::describe_update_reply(method_name, blob))
+}
+
+fn print_neuron_outcomes(
+    outcomes: &[(IngressWithRequestId, Option<Result<String, String>>)],
+) -> AnyhowResult {
+    let mut rows = Vec::new();
+    for (message, outcome) in outcomes {
+        let (_, _, method_name, arg) = message.ingress.parse_raw()?;
+        if method_name != "manage_neuron" {
+            continue;
+        }
+        let args = Decode!(&arg, ManageNeuronTarget)?;
+        let neuron = match (args.id, args.neuron_id_or_subaccount) {
+            (Some(id), _) | (_, Some(NeuronIdOrSubaccount::NeuronId(id))) => id.id.to_string(),
+            (_, Some(NeuronIdOrSubaccount::Subaccount(subaccount))) => hex::encode(subaccount),
+            _ => "-".to_string(),
+        };
+        let outcome = match outcome {
+            Some(Ok(summary)) => summary.clone(),
+            Some(Err(error)) => error.lines().next().unwrap_or_default().to_string(),
+            None => String::new(),
+        };
+        rows.push((neuron, outcome));
+    }
+    if rows
+        .iter()
+        .map(|(neuron, _)| neuron)
+        .collect::<HashSet<_>>()
+        .len()
+        < 2
+    {
+        return Ok(());
+    }
+    let width = rows
+        .iter()
+        .map(|(neuron, _)| neuron.len())
+        .max()
+        .unwrap_or_default()
+        .max("Neuron".len());
+    println!("{:width$}  Outcome", "Neuron", width = width);
+    for (neuron, outcome) in rows {
+        println!("{:width$}  {}", neuron, outcome, width = width);
+    }
     Ok(())
 }
 
-async fn send(message: &Ingress, opts: &Opts) -> AnyhowResult {
-    let (sender, canister_id, method_name, args) = message.parse()?;
+/// Describes the envelope of the message and of its request status queries, flagging
+/// expired messages.
+fn describe_envelope(message: &Ingress, statuses: &[RequestStatus]) -> AnyhowResult<Vec<String>> {
+    let envelope = Envelope::decode(&message.content)?;
+    let request_id = envelope.request_id()?;
+    let mut lines = vec![format!("  Expiry:      {}", describe_expiry(&envelope))];
+    lines.push(format!(
+        "  Nonce:       {}",
+        envelope
+            .nonce()
+            .map_or_else(|| "none".to_string(), hex::encode)
+    ));
+    if message.call_type == "update" {
+        lines.push(format!("  Request id:  0x{}", hex::encode(request_id)));
+    }
+    for status in statuses {
+        let status_envelope = Envelope::decode(&status.content)?;
+        lines.push("  Status query:".to_string());
+        lines.push(format!(
+            "    Expiry:    {}",
+            describe_expiry(&status_envelope)
+        ));
+        for path in status_envelope.paths() {
+            lines.push(format!(
+                "    Path:      /{}",
+                path.iter()
+                    .map(|label| describe_label(label))
+                    .collect::<Vec<_>>()
+                    .join("/")
+            ));
+        }
+    }
+    Ok(lines)
+}
+
+/// Returns the expiry as a date with the time remaining, or flags it as expired.
+fn describe_expiry(envelope: &Envelope) -> String {
+    let expiry = match envelope.ingress_expiry() {
+        Some(expiry) => expiry / 1_000_000_000,
+        None => return "none".to_string(),
+    };
+    let now = now_seconds();
+    if expiry > now {
+        format!(
+            "{} (in {})",
+            format_timestamp(expiry),
+            format_duration(expiry - now)
+        )
+    } else {
+        format!(
+            "{} (EXPIRED {} ago)",
+            format_timestamp(expiry),
+            format_duration(now - expiry)
+        )
+    }
+}
+
+/// Shows a path label as text if it is printable, as hex otherwise.
+fn describe_label(label: &[u8]) -> String {
+    match std::str::from_utf8(label) {
+        Ok(text) if text.chars().all(|c| c.is_ascii_graphic()) => text.to_string(),
+        _ => hex::encode(label),
+    }
+}
+
+/// Shows the message, asks for confirmation and sends it. Returns what became of it.
+async fn send(
+    message: &Ingress,
+    statuses: &[RequestStatus],
+    opts: &Opts,
+) -> AnyhowResult<Disposition> {
+    let (sender, canister_id, method_name, arg) = message.parse_raw()?;
+    let args = get_idl_string(&arg, canister_id, &method_name, "args");
 
     if !opts.raw {
-        println!("Sending message with\n");
-        println!("  Call type:   {}", message.call_type);
-        println!("  Sender:      {}", sender);
-        println!("  Canister id: {}", canister_id);
-        println!("  Method name: {}", method_name);
-        println!("  Arguments:   {}", args.map_err(|e| anyhow!(e))?);
+        let mut lines = vec![
+            "Sending message with\n".to_string(),
+            format!("  Call type:   {}", message.call_type),
+            format!("  Sender:      {}", sender),
+            format!("  Canister id: {}", canister_id),
+            format!("  Method name: {}", method_name),
+            format!("  Arguments:   {}", args.map_err(|e| anyhow!(e))?),
+        ];
+        if canister_id == governance_canister_id() && method_name == "manage_neuron" {
+            if let Some(description) = describe_manage_neuron(&arg) {
+                lines.push(format!("  {}", description));
+            }
+        }
+        if opts.show_envelope {
+            lines.extend(describe_envelope(message, statuses)?);
+        }
+        // With csv and json reports, stdout is kept for the report.
+        if opts.report_format == ReportFormat::Table {
+            println!("{}", lines.join("\n"));
+        } else {
+            eprintln!("{}", lines.join("\n"));
+        }
     }
 
     if opts.dry_run {
-        return Ok(());
+        return Ok(Disposition::Sent);
     }
 
     if message.call_type == "update" && !opts.yes {
-        println!("\nDo you want to send this message? [y/N]");
-        let mut input = String::new();
-        std::io::stdin().read_line(&mut input)?;
-        if !["y", "yes"].contains(&input.to_lowercase().trim()) {
-            std::process::exit(0);
+        let choice = confirm("Do you want to send this message?", opts)?;
+        if choice != Disposition::Sent {
+            return Ok(choice);
         }
     }
 
+    record_sending(message, opts)?;
     match send_ingress(message).await? {
         IngressResult::QueryResponse(response) => {
             if opts.raw {
                 write_to_stdout(&response)?;
+            } else if canister_id == governance_canister_id() && method_name == "list_neurons" {
+                println!("{}", neuron_report::render(&response, opts.report_format)?);
+            } else if let Some(rendered) = render_reply(canister_id, &method_name, &response)? {
+                println!("{}", rendered);
+            } else if let Some(rendered) = sns::render_reply(canister_id, &method_name, &response) {
+                println!("{}", rendered);
+            } else if let Some(rendered) = ckbtc::render_reply(&method_name, &response) {
+                println!("{}", rendered);
             } else {
                 println!(
                     "Response: {}",
@@ -107,12 +1071,13 @@ async fn send(message: &Ingress, opts: &Opts) -> AnyhowResult {
             }
         }
         IngressResult::RequestId(id) => {
+            record_in_journal(message, "submitted", opts);
             if !opts.raw {
                 println!("RequestId: 0x{}", String::from(id));
             }
         }
     };
-    Ok(())
+    Ok(Disposition::Sent)
 }
 
 fn write_to_stdout(blob: &[u8]) -> AnyhowResult {
@@ -0,0 +1,48 @@
+use crate::{
+    commands::sns::sign_commands,
+    lib::{
+        signing::IngressWithRequestId,
+        sns::{parse_neuron_id, Command, Follow, NeuronId},
+        AnyhowResult,
+    },
+};
+use candid::Principal;
+use clap::Parser;
+use ic_agent::Agent;
+
+/// Signs the following of other SNS neurons on a proposal function. Without followees,
+/// the neuron stops following on the function.
+#[derive(Parser)]
+pub struct Opts {
+    /// The id of the neuron, in hex.
+    neuron_id: String,
+
+    /// The id of the proposal function (0 follows on all functions without specific followees).
+    #[clap(long)]
+    function_id: u64,
+
+    /// The ids of the neurons to follow, in hex.
+    #[clap(long, multiple_values(true))]
+    followees: Vec<String>,
+}
+
+pub fn exec(
+    agent: Agent,
+    governance: Principal,
+    opts: Opts,
+) -> AnyhowResult<Vec<IngressWithRequestId>> {
+    let followees = opts
+        .followees
+        .iter()
+        .map(|id| parse_neuron_id(id).map(|id| NeuronId { id }))
+        .collect::<AnyhowResult<Vec<_>>>()?;
+    sign_commands(
+        agent,
+        governance,
+        parse_neuron_id(&opts.neuron_id)?,
+        vec![Command::Follow(Follow {
+            function_id: opts.function_id,
+            followees,
+        })],
+    )
+}
@@ -0,0 +1,41 @@
+use crate::lib::{
+    signing::{sign_ingress, Ingress},
+    sns::ListNeurons,
+    AnyhowResult,
+};
+use anyhow::anyhow;
+use candid::{Encode, Principal};
+use clap::Parser;
+use ic_agent::Agent;
+
+/// Signs the query for the SNS neurons of a principal.
+#[derive(Parser)]
+pub struct Opts {
+    /// The principal whose neurons are listed, defaults to the signer.
+    #[clap(long)]
+    principal: Option<Principal>,
+
+    /// The maximum number of neurons returned.
+    #[clap(long, default_value = "100")]
+    limit: u32,
+}
+
+pub fn exec(agent: Agent, governance: Principal, opts: Opts) -> AnyhowResult<Vec<Ingress>> {
+    let principal = match opts.principal {
+        Some(principal) => principal,
+        None => agent.get_principal().map_err(|err| anyhow!(err))?,
+    };
+    let args = Encode!(&ListNeurons {
+        of_principal: Some(principal),
+        limit: opts.limit,
+        start_page_at: None,
+    })?;
+    let method_name = "list_neurons";
+    Ok(vec![sign_ingress(
+        agent,
+        governance,
+        method_name,
+        crate::lib::is_query(governance, method_name),
+        args,
+    )?])
+}
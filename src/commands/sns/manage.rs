@@ -0,0 +1,175 @@
+use crate::{
+    commands::sns::sign_commands,
+    lib::{
+        duration::parse_duration,
+        signing::IngressWithRequestId,
+        sns::{
+            parse_neuron_id, parse_permission, AddNeuronPermissions, ChangeAutoStakeMaturity,
+            Command, Configure, Disburse, DisburseMaturity, Empty, IncreaseDissolveDelay,
+            NeuronPermissionList, Operation, RemoveNeuronPermissions, StakeMaturity,
+        },
+        AnyhowResult,
+    },
+};
+use anyhow::anyhow;
+use candid::Principal;
+use clap::Parser;
+use ic_agent::Agent;
+use std::convert::TryFrom;
+
+/// Signs SNS neuron configuration changes.
+#[derive(Parser)]
+pub struct Opts {
+    /// The id of the neuron, in hex.
+    neuron_id: String,
+
+    /// Dissolve delay to add, in seconds or as a duration like "6m" or "1y 3d".
+    #[clap(short, long)]
+    additional_dissolve_delay_seconds: Option<String>,
+
+    /// Start dissolving.
+    #[clap(long)]
+    start_dissolving: bool,
+
+    /// Stop dissolving.
+    #[clap(long)]
+    stop_dissolving: bool,
+
+    /// Disburse the entire stake to the signer's account.
+    #[clap(long)]
+    disburse: bool,
+
+    /// Stake the percentage (between 1 and 100) of the maturity of the neuron.
+    #[clap(long)]
+    stake_maturity: Option<u32>,
+
+    /// Disburse the percentage (between 1 and 100) of the maturity of the neuron to the
+    /// signer's account.
+    #[clap(long)]
+    disburse_maturity: Option<u32>,
+
+    /// Automatically stake all future maturity of the neuron.
+    #[clap(long)]
+    enable_auto_stake_maturity: bool,
+
+    /// Stop automatically staking the maturity of the neuron.
+    #[clap(long)]
+    disable_auto_stake_maturity: bool,
+
+    /// Grant the permissions given with --permissions to this principal.
+    #[clap(long)]
+    add_permissions: Option<Principal>,
+
+    /// Revoke the permissions given with --permissions from this principal.
+    #[clap(long)]
+    remove_permissions: Option<Principal>,
+
+    /// Neuron permissions, e.g. vote, submit-proposal or manage-principals.
+    #[clap(long, multiple_values(true))]
+    permissions: Vec<String>,
+}
+
+pub fn exec(
+    agent: Agent,
+    governance: Principal,
+    opts: Opts,
+) -> AnyhowResult<Vec<IngressWithRequestId>> {
+    let subaccount = parse_neuron_id(&opts.neuron_id)?;
+    let configure = |operation| {
+        Command::Configure(Configure {
+            operation: Some(operation),
+        })
+    };
+    let check_percentage = |percentage: u32| {
+        if percentage == 0 || percentage > 100 {
+            Err(anyhow!("The percentage must be a number from 1 to 100"))
+        } else {
+            Ok(percentage)
+        }
+    };
+    let mut commands = Vec::new();
+
+    if let Some(delay) = opts.additional_dissolve_delay_seconds {
+        commands.push(configure(Operation::IncreaseDissolveDelay(
+            IncreaseDissolveDelay {
+                additional_dissolve_delay_seconds: u32::try_from(parse_duration(&delay)?)
+                    .map_err(|_| anyhow!("The dissolve delay {} is too large", delay))?,
+            },
+        )));
+    }
+
+    if opts.start_dissolving {
+        commands.push(configure(Operation::StartDissolving(Empty {})));
+    }
+
+    if opts.stop_dissolving {
+        commands.push(configure(Operation::StopDissolving(Empty {})));
+    }
+
+    if opts.disburse {
+        commands.push(Command::Disburse(Disburse {
+            to_account: None,
+            amount: None,
+        }));
+    }
+
+    if let Some(percentage) = opts.stake_maturity {
+        commands.push(Command::StakeMaturity(StakeMaturity {
+            percentage_to_stake: Some(check_percentage(percentage)?),
+        }));
+    }
+
+    if let Some(percentage) = opts.disburse_maturity {
+        commands.push(Command::DisburseMaturity(DisburseMaturity {
+            to_account: None,
+            percentage_to_disburse: check_percentage(percentage)?,
+        }));
+    }
+
+    if opts.enable_auto_stake_maturity && opts.disable_auto_stake_maturity {
+        return Err(anyhow!(
+            "Auto-staking of maturity can't be both enabled and disabled"
+        ));
+    }
+
+    if opts.enable_auto_stake_maturity || opts.disable_auto_stake_maturity {
+        commands.push(configure(Operation::ChangeAutoStakeMaturity(
+            ChangeAutoStakeMaturity {
+                requested_setting_for_auto_stake_maturity: opts.enable_auto_stake_maturity,
+            },
+        )));
+    }
+
+    let permissions = opts
+        .permissions
+        .iter()
+        .map(|permission| parse_permission(permission))
+        .collect::<AnyhowResult<Vec<_>>>()?;
+    if (opts.add_permissions.is_some() || opts.remove_permissions.is_some())
+        && permissions.is_empty()
+    {
+        return Err(anyhow!("No permissions given with --permissions"));
+    }
+
+    if let Some(principal) = opts.add_permissions {
+        commands.push(Command::AddNeuronPermissions(AddNeuronPermissions {
+            permissions_to_add: Some(NeuronPermissionList {
+                permissions: permissions.clone(),
+            }),
+            principal_id: Some(principal),
+        }));
+    }
+
+    if let Some(principal) = opts.remove_permissions {
+        commands.push(Command::RemoveNeuronPermissions(RemoveNeuronPermissions {
+            permissions_to_remove: Some(NeuronPermissionList { permissions }),
+            principal_id: Some(principal),
+        }));
+    }
+
+    if commands.is_empty() {
+        return Err(anyhow!("No instructions provided"));
+    }
+
+    sign_commands(agent, governance, subaccount, commands)
+}
@@ -0,0 +1,79 @@
+//! Commands for the governance and ledger canisters of an SNS.
+use crate::{
+    commands::print,
+    lib::{
+        signing::{sign_ingress_with_request_status_query, IngressWithRequestId},
+        sns::{Command, ManageNeuron},
+        AnyhowResult,
+    },
+};
+use candid::{Encode, Principal};
+use clap::Parser;
+use ic_agent::Agent;
+
+mod follow;
+mod list_neurons;
+mod manage;
+mod stake;
+mod vote;
+
+/// Signs messages to the governance and ledger canisters of an SNS.
+#[derive(Parser)]
+pub struct Opts {
+    /// The id of the SNS governance canister.
+    #[clap(long)]
+    governance_canister_id: Principal,
+
+    #[clap(subcommand)]
+    command: SnsCommand,
+}
+
+#[derive(Parser)]
+enum SnsCommand {
+    Stake(stake::Opts),
+    Manage(manage::Opts),
+    Follow(follow::Opts),
+    Vote(vote::Opts),
+    ListNeurons(list_neurons::Opts),
+}
+
+pub fn exec(agent: Agent, opts: Opts) -> AnyhowResult {
+    let governance = opts.governance_canister_id;
+    match opts.command {
+        SnsCommand::Stake(opts) => stake::exec(agent, governance, opts).and_then(|out| print(&out)),
+        SnsCommand::Manage(opts) => {
+            manage::exec(agent, governance, opts).and_then(|out| print(&out))
+        }
+        SnsCommand::Follow(opts) => {
+            follow::exec(agent, governance, opts).and_then(|out| print(&out))
+        }
+        SnsCommand::Vote(opts) => vote::exec(agent, governance, opts).and_then(|out| print(&out)),
+        SnsCommand::ListNeurons(opts) => {
+            list_neurons::exec(agent, governance, opts).and_then(|out| print(&out))
+        }
+    }
+}
+
+/// Signs a `manage_neuron` message for every command, addressed to the neuron with
+/// the given subaccount.
+fn sign_commands(
+    agent: Agent,
+    governance: Principal,
+    subaccount: Vec<u8>,
+    commands: Vec<Command>,
+) -> AnyhowResult<Vec<IngressWithRequestId>> {
+    let mut generated = Vec::new();
+    for command in commands {
+        let args = Encode!(&ManageNeuron {
+            subaccount: subaccount.clone(),
+            command: Some(command),
+        })?;
+        generated.push(sign_ingress_with_request_status_query(
+            agent.clone(),
+            governance,
+            "manage_neuron",
+            args,
+        )?);
+    }
+    Ok(generated)
+}
@@ -0,0 +1,77 @@
+use crate::{
+    commands::{sns::sign_commands, transfer::parse_icpts},
+    lib::{
+        icrc1::{Account, TransferArg},
+        signing::{sign_ingress_with_request_status_query, IngressWithRequestId},
+        sns::{By, ClaimOrRefresh, Command, MemoAndController},
+        AnyhowResult,
+    },
+};
+use anyhow::anyhow;
+use candid::{Encode, Nat, Principal};
+use clap::Parser;
+use ic_agent::Agent;
+use ic_base_types::PrincipalId;
+use ic_nns_governance::governance::compute_neuron_staking_subaccount;
+
+/// Signs the transfer of tokens to the staking subaccount of an SNS neuron and the
+/// claim or refresh of the neuron.
+#[derive(Parser)]
+pub struct Opts {
+    /// The id of the SNS ledger canister.
+    #[clap(long)]
+    ledger_canister_id: Principal,
+
+    /// Amount of tokens to stake (with up to 8 decimal digits after comma). Without
+    /// an amount, the neuron is only claimed or refreshed.
+    #[clap(long)]
+    amount: Option<String>,
+
+    /// The nonce of the neuron, which determines its staking subaccount.
+    #[clap(long)]
+    memo: u64,
+}
+
+pub fn exec(
+    agent: Agent,
+    governance: Principal,
+    opts: Opts,
+) -> AnyhowResult<Vec<IngressWithRequestId>> {
+    let controller = agent.get_principal().map_err(|err| anyhow!(err))?;
+    let subaccount = compute_neuron_staking_subaccount(PrincipalId(controller), opts.memo);
+    let mut messages = Vec::new();
+    if let Some(amount) = opts.amount {
+        let amount = parse_icpts(&amount)
+            .map_err(|err| anyhow!("Couldn't parse the amount: {}", err))?
+            .get_e8s();
+        let args = Encode!(&TransferArg {
+            from_subaccount: None,
+            to: Account {
+                owner: governance,
+                subaccount: Some(subaccount.0.to_vec()),
+            },
+            amount: Nat::from(amount),
+            fee: None,
+            memo: None,
+            created_at_time: None,
+        })?;
+        messages.push(sign_ingress_with_request_status_query(
+            agent.clone(),
+            opts.ledger_canister_id,
+            "icrc1_transfer",
+            args,
+        )?);
+    }
+    messages.extend(sign_commands(
+        agent,
+        governance,
+        subaccount.0.to_vec(),
+        vec![Command::ClaimOrRefresh(ClaimOrRefresh {
+            by: Some(By::MemoAndController(MemoAndController {
+                controller: Some(controller),
+                memo: opts.memo,
+            })),
+        })],
+    )?);
+    Ok(messages)
+}
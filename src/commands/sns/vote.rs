@@ -0,0 +1,50 @@
+use crate::{
+    commands::sns::sign_commands,
+    lib::{
+        signing::IngressWithRequestId,
+        sns::{parse_neuron_id, Command, ProposalId, RegisterVote},
+        AnyhowResult,
+    },
+};
+use anyhow::anyhow;
+use candid::Principal;
+use clap::Parser;
+use ic_agent::Agent;
+
+/// Signs a vote of an SNS neuron on a proposal.
+#[derive(Parser)]
+pub struct Opts {
+    /// The id of the neuron, in hex.
+    neuron_id: String,
+
+    /// The id of the proposal.
+    #[clap(long)]
+    proposal_id: u64,
+
+    /// The vote: yes or no.
+    #[clap(long)]
+    vote: String,
+}
+
+pub fn exec(
+    agent: Agent,
+    governance: Principal,
+    opts: Opts,
+) -> AnyhowResult<Vec<IngressWithRequestId>> {
+    let vote = match opts.vote.to_lowercase().as_str() {
+        "yes" | "y" => 1,
+        "no" | "n" => 2,
+        _ => return Err(anyhow!("The vote must be either yes or no")),
+    };
+    sign_commands(
+        agent,
+        governance,
+        parse_neuron_id(&opts.neuron_id)?,
+        vec![Command::RegisterVote(RegisterVote {
+            vote,
+            proposal: Some(ProposalId {
+                id: opts.proposal_id,
+            }),
+        })],
+    )
+}
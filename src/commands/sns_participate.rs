@@ -0,0 +1,96 @@
+use crate::{
+    commands::transfer::parse_icpts,
+    lib::{
+        ledger_canister_id,
+        signing::{sign_ingress_with_request_status_query, IngressWithRequestId},
+        sns::{NewSaleTicketRequest, RefreshBuyerTokensRequest},
+        AnyhowResult,
+    },
+};
+use anyhow::anyhow;
+use candid::{Encode, Principal};
+use clap::Parser;
+use ic_agent::Agent;
+use ic_base_types::PrincipalId;
+use ledger_canister::{AccountIdentifier, Memo, SendArgs, Subaccount, TRANSACTION_FEE};
+
+/// Signs the participation in an SNS decentralization swap: the transfer of ICP to the
+/// buyer's subaccount of the swap canister and the notification of the swap.
+#[derive(Parser)]
+pub struct Opts {
+    /// The id of the SNS swap canister.
+    #[clap(long)]
+    swap_canister_id: Principal,
+
+    /// Amount of ICP to participate with (with up to 8 decimal digits after comma).
+    #[clap(long)]
+    amount: String,
+
+    /// Create a sale ticket for the amount before the transfer.
+    #[clap(long)]
+    ticket: bool,
+
+    /// The confirmation text of the swap, if it requires participants to confirm one.
+    #[clap(long)]
+    confirmation_text: Option<String>,
+}
+
+pub fn exec(agent: Agent, opts: Opts) -> AnyhowResult<Vec<IngressWithRequestId>> {
+    let buyer = agent.get_principal().map_err(|err| anyhow!(err))?;
+    let amount =
+        parse_icpts(&opts.amount).map_err(|err| anyhow!("Couldn't parse the amount: {}", err))?;
+    let mut messages = Vec::new();
+
+    if opts.ticket {
+        let args = Encode!(&NewSaleTicketRequest {
+            amount_icp_e8s: amount.get_e8s(),
+            subaccount: None,
+        })?;
+        messages.push(sign_ingress_with_request_status_query(
+            agent.clone(),
+            opts.swap_canister_id,
+            "new_sale_ticket",
+            args,
+        )?);
+    }
+
+    let args = Encode!(&SendArgs {
+        memo: Memo(0),
+        amount,
+        fee: TRANSACTION_FEE,
+        from_subaccount: None,
+        to: AccountIdentifier::new(
+            PrincipalId(opts.swap_canister_id),
+            Some(principal_to_subaccount(&buyer)),
+        ),
+        created_at_time: None,
+    })?;
+    messages.push(sign_ingress_with_request_status_query(
+        agent.clone(),
+        ledger_canister_id(),
+        "send_dfx",
+        args,
+    )?);
+
+    let args = Encode!(&RefreshBuyerTokensRequest {
+        confirmation_text: opts.confirmation_text,
+        buyer: buyer.to_string(),
+    })?;
+    messages.push(sign_ingress_with_request_status_query(
+        agent,
+        opts.swap_canister_id,
+        "refresh_buyer_tokens",
+        args,
+    )?);
+    Ok(messages)
+}
+
+/// Returns the subaccount of the swap canister holding the participation of the buyer:
+/// the length of the principal followed by its bytes.
+fn principal_to_subaccount(principal: &Principal) -> Subaccount {
+    let bytes = principal.as_slice();
+    let mut subaccount = [0; 32];
+    subaccount[0] = bytes.len() as u8;
+    subaccount[1..1 + bytes.len()].copy_from_slice(bytes);
+    Subaccount(subaccount)
+}
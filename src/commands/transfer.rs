@@ -56,7 +56,7 @@ pub fn exec(agent: Agent, opts: Opts) -> AnyhowResult<Vec<IngressWithRequestId>>
     Ok(vec![msg])
 }
 
-fn parse_icpts(amount: &str) -> Result<Tokens, String> {
+pub fn parse_icpts(amount: &str) -> Result<Tokens, String> {
     let parse = |s: &str| {
         s.parse::<u64>()
             .map_err(|err| format!("Couldn't parse as u64: {:?}", err))
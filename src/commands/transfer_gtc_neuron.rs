@@ -0,0 +1,43 @@
+use crate::{
+    commands::{claim_gtc_neurons::get_gtc_principal, neuron_manage::parse_neuron_id},
+    lib::{
+        governance_canister_id,
+        signing::{sign_ingress_with_request_status_query, IngressWithRequestId},
+        AnyhowResult,
+    },
+};
+use anyhow::anyhow;
+use candid::Encode;
+use clap::Parser;
+use ic_agent::Agent;
+use ic_nns_common::pb::v1::NeuronId;
+
+/// Signs a transfer of the stake of a genesis token neuron to another neuron.
+#[derive(Parser)]
+pub struct Opts {
+    /// The id of the genesis token neuron to transfer.
+    donor_neuron_id: String,
+
+    /// The id of the neuron receiving the stake.
+    recipient_neuron_id: String,
+}
+
+pub fn exec(
+    agent: Agent,
+    pem: Option<String>,
+    opts: Opts,
+) -> AnyhowResult<Vec<IngressWithRequestId>> {
+    get_gtc_principal(pem)?;
+    let donor = parse_neuron_id(&opts.donor_neuron_id)?;
+    let recipient = parse_neuron_id(&opts.recipient_neuron_id)?;
+    if donor == recipient {
+        return Err(anyhow!("Can't transfer neuron {} to itself", donor));
+    }
+    let args = Encode!(&NeuronId { id: donor }, &NeuronId { id: recipient })?;
+    Ok(vec![sign_ingress_with_request_status_query(
+        agent,
+        governance_canister_id(),
+        "transfer_gtc_neuron",
+        args,
+    )?])
+}
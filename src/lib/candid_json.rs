@@ -0,0 +1,168 @@
+//! Rendering of candid values as JSON, for tools reading the output of `quill`.
+//!
+//! The values are rendered with the types of the method when its candid is known, so that
+//! records keep their field names and empty blobs are still blobs. To be read without loss
+//! by any JSON parser, `nat`, `int`, `nat64` and `int64` values are strings, blobs are hex
+//! strings, account ids (`record { hash : vec nat8 }`) are hex strings with their checksum,
+//! as shown by the ledger, and principals are text.
+use crate::lib::{get_idl_args, get_method_type};
+use candid::parser::typing::TypeEnv;
+use candid::parser::value::{IDLArgs, IDLField, IDLValue, VariantValue};
+use candid::types::{Field, Label, Type};
+use ic_types::Principal;
+use ledger_canister::AccountIdentifier;
+use serde_json::{json, Map, Value};
+use std::convert::TryFrom;
+
+/// Decodes the arguments (`part` is "args") or the reply ("rets") of a method as JSON, like
+/// `get_idl_args` decodes them.
+pub fn get_idl_json(
+    blob: &[u8],
+    canister_id: Principal,
+    method_name: &str,
+    part: &str,
+) -> Result<Value, String> {
+    let args = get_idl_args(blob, canister_id, method_name, part)?;
+    Ok(match get_method_type(canister_id, method_name) {
+        Some((env, func)) => {
+            let types = if part == "args" {
+                &func.args
+            } else {
+                &func.rets
+            };
+            args_to_json(&args, &env, types)
+        }
+        None => args_to_json(&args, &TypeEnv::new(), &[]),
+    })
+}
+
+/// Converts the values to a JSON array, following the types where they are given. Records
+/// and variants become objects keyed by their field names, and options their value or `null`.
+pub fn args_to_json(args: &IDLArgs, env: &TypeEnv, types: &[Type]) -> Value {
+    Value::Array(
+        args.args
+            .iter()
+            .enumerate()
+            .map(|(index, value)| value_to_json(value, types.get(index), env))
+            .collect(),
+    )
+}
+
+fn value_to_json(value: &IDLValue, ty: Option<&Type>, env: &TypeEnv) -> Value {
+    if let IDLValue::Record(fields) = value {
+        if let Some(account) = account_identifier(fields) {
+            return account;
+        }
+    }
+    let ty = ty.and_then(|ty| env.trace_type(ty).ok());
+    match (value, &ty) {
+        (IDLValue::Vec(values), Some(Type::Vec(inner)))
+            if is_nat8(inner, env) && values.iter().all(|v| matches!(v, IDLValue::Nat8(_))) =>
+        {
+            blob(values)
+        }
+        (IDLValue::Vec(values), None)
+            if !values.is_empty() && values.iter().all(|v| matches!(v, IDLValue::Nat8(_))) =>
+        {
+            blob(values)
+        }
+        (IDLValue::Vec(values), Some(Type::Vec(inner))) => Value::Array(
+            values
+                .iter()
+                .map(|value| value_to_json(value, Some(inner.as_ref()), env))
+                .collect(),
+        ),
+        (IDLValue::Vec(values), _) => Value::Array(
+            values
+                .iter()
+                .map(|value| value_to_json(value, None, env))
+                .collect(),
+        ),
+        (IDLValue::Opt(value), Some(Type::Opt(inner))) => {
+            value_to_json(value, Some(inner.as_ref()), env)
+        }
+        (IDLValue::Opt(value), _) => value_to_json(value, None, env),
+        (IDLValue::Record(fields), Some(Type::Record(types))) => fields_to_json(fields, types, env),
+        (IDLValue::Record(fields), _) => fields_to_json(fields, &[], env),
+        (IDLValue::Variant(VariantValue(field, _)), Some(Type::Variant(types))) => {
+            fields_to_json(std::slice::from_ref(field.as_ref()), types, env)
+        }
+        (IDLValue::Variant(VariantValue(field, _)), _) => {
+            fields_to_json(std::slice::from_ref(field.as_ref()), &[], env)
+        }
+        (IDLValue::Bool(value), _) => json!(value),
+        (IDLValue::Null, _) | (IDLValue::None, _) | (IDLValue::Reserved, _) => Value::Null,
+        (IDLValue::Text(text), _) | (IDLValue::Number(text), _) => json!(text),
+        (IDLValue::Float32(number), _) => json!(number),
+        (IDLValue::Float64(number), _) => json!(number),
+        (IDLValue::Principal(principal), _) | (IDLValue::Service(principal), _) => {
+            json!(principal.to_text())
+        }
+        (IDLValue::Func(principal, method), _) => {
+            json!({ "principal": principal.to_text(), "method": method })
+        }
+        (IDLValue::Nat(number), _) => json!(number.0.to_string()),
+        (IDLValue::Int(number), _) => json!(number.0.to_string()),
+        (IDLValue::Nat64(number), _) => json!(number.to_string()),
+        (IDLValue::Int64(number), _) => json!(number.to_string()),
+        (IDLValue::Nat8(number), _) => json!(number),
+        (IDLValue::Nat16(number), _) => json!(number),
+        (IDLValue::Nat32(number), _) => json!(number),
+        (IDLValue::Int8(number), _) => json!(number),
+        (IDLValue::Int16(number), _) => json!(number),
+        (IDLValue::Int32(number), _) => json!(number),
+    }
+}
+
+fn fields_to_json(fields: &[IDLField], types: &[Field], env: &TypeEnv) -> Value {
+    Value::Object(
+        fields
+            .iter()
+            .map(|field| {
+                let ty = types
+                    .iter()
+                    .find(|ty| ty.id.get_id() == field.id.get_id())
+                    .map(|ty| &ty.ty);
+                (field.id.to_string(), value_to_json(&field.val, ty, env))
+            })
+            .collect::<Map<_, _>>(),
+    )
+}
+
+/// Renders a record with a single 28-byte `hash` field as an account id, in the
+/// checksummed hex form used to transfer ICP.
+fn account_identifier(fields: &[IDLField]) -> Option<Value> {
+    let field = match fields {
+        [field] if field.id.get_id() == Label::Named("hash".to_string()).get_id() => field,
+        _ => return None,
+    };
+    let bytes = match &field.val {
+        IDLValue::Vec(values) => values
+            .iter()
+            .map(|value| match value {
+                IDLValue::Nat8(byte) => Some(*byte),
+                _ => None,
+            })
+            .collect::<Option<Vec<_>>>()?,
+        _ => return None,
+    };
+    let hash = <[u8; 28]>::try_from(bytes.as_slice()).ok()?;
+    Some(json!(AccountIdentifier { hash }.to_string()))
+}
+
+fn is_nat8(ty: &Type, env: &TypeEnv) -> bool {
+    matches!(env.trace_type(ty), Ok(Type::Nat8))
+}
+
+/// Renders a `vec nat8` as a hex string.
+fn blob(values: &[IDLValue]) -> Value {
+    json!(hex::encode(
+        values
+            .iter()
+            .filter_map(|value| match value {
+                IDLValue::Nat8(byte) => Some(*byte),
+                _ => None,
+            })
+            .collect::<Vec<_>>()
+    ))
+}
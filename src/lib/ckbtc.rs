@@ -0,0 +1,284 @@
+//! Types of the ckBTC minter, and the rendering of its replies.
+use crate::lib::governance::format_e8s;
+use candid::{CandidType, Decode, Deserialize, Principal};
+
+/// The id of the ckBTC minter on the mainnet.
+pub const MINTER_CANISTER_ID: &str = "mqygn-kiaaa-aaaar-qaadq-cai";
+
+/// The id of the ckBTC ledger on the mainnet.
+pub const LEDGER_CANISTER_ID: &str = "mxzaz-hqaaa-aaaar-qaada-cai";
+
+/// The fee of a ckBTC ledger transaction, in satoshi.
+pub const TRANSACTION_FEE: u64 = 10;
+
+pub fn minter_canister_id() -> Principal {
+    Principal::from_text(MINTER_CANISTER_ID).unwrap()
+}
+
+pub fn ledger_canister_id() -> Principal {
+    Principal::from_text(LEDGER_CANISTER_ID).unwrap()
+}
+
+/// The argument of `get_btc_address` and `update_balance`.
+#[derive(CandidType)]
+pub struct OwnerArgs {
+    pub owner: Option<Principal>,
+    pub subaccount: Option<Vec<u8>>,
+}
+
+/// The argument of `retrieve_btc_with_approval`.
+#[derive(CandidType)]
+pub struct RetrieveBtcWithApprovalArgs {
+    pub from_subaccount: Option<Vec<u8>>,
+    pub address: String,
+    pub amount: u64,
+}
+
+/// The argument of `retrieve_btc_status`.
+#[derive(CandidType)]
+pub struct RetrieveBtcStatusArgs {
+    pub block_index: u64,
+}
+
+#[derive(CandidType, Deserialize)]
+pub struct OutPoint {
+    pub txid: Vec<u8>,
+    pub vout: u32,
+}
+
+#[derive(CandidType, Deserialize)]
+pub struct Utxo {
+    pub height: u32,
+    pub value: u64,
+    pub outpoint: OutPoint,
+}
+
+#[derive(CandidType, Deserialize)]
+pub struct PendingUtxo {
+    pub confirmations: u32,
+    pub value: u64,
+    pub outpoint: OutPoint,
+}
+
+#[derive(CandidType, Deserialize)]
+pub enum UtxoStatus {
+    ValueTooSmall(Utxo),
+    Tainted(Utxo),
+    Checked(Utxo),
+    Minted {
+        block_index: u64,
+        minted_amount: u64,
+        utxo: Utxo,
+    },
+}
+
+#[derive(CandidType, Deserialize)]
+pub enum UpdateBalanceError {
+    GenericError {
+        error_message: String,
+        error_code: u64,
+    },
+    TemporarilyUnavailable(String),
+    AlreadyProcessing,
+    NoNewUtxos {
+        required_confirmations: u32,
+        pending_utxos: Option<Vec<PendingUtxo>>,
+        current_confirmations: Option<u32>,
+    },
+}
+
+#[derive(CandidType, Deserialize)]
+pub struct RetrieveBtcOk {
+    pub block_index: u64,
+}
+
+#[derive(CandidType, Deserialize)]
+pub enum RetrieveBtcWithApprovalError {
+    MalformedAddress(String),
+    GenericError {
+        error_message: String,
+        error_code: u64,
+    },
+    TemporarilyUnavailable(String),
+    InsufficientAllowance {
+        allowance: u64,
+    },
+    AlreadyProcessing,
+    AmountTooLow(u64),
+    InsufficientFunds {
+        balance: u64,
+    },
+}
+
+#[derive(CandidType, Deserialize)]
+pub struct Txid {
+    pub txid: Vec<u8>,
+}
+
+#[derive(CandidType, Deserialize)]
+pub enum RetrieveBtcStatus {
+    Signing,
+    Confirmed(Txid),
+    Sending(Txid),
+    AmountTooLow,
+    Unknown,
+    Submitted(Txid),
+    Pending,
+}
+
+/// Formats a Bitcoin transaction id the way block explorers show it: as hex with the
+/// bytes in reverse order.
+fn format_txid(txid: &[u8]) -> String {
+    hex::encode(txid.iter().rev().cloned().collect::<Vec<_>>())
+}
+
+fn format_utxo(utxo: &Utxo) -> String {
+    format!(
+        "{}:{} ({} BTC)",
+        format_txid(&utxo.outpoint.txid),
+        utxo.outpoint.vout,
+        format_e8s(utxo.value)
+    )
+}
+
+fn render_update_balance_error(error: &UpdateBalanceError) -> String {
+    match error {
+        UpdateBalanceError::GenericError {
+            error_message,
+            error_code,
+        } => format!("Error: {} (code {})", error_message, error_code),
+        UpdateBalanceError::TemporarilyUnavailable(message) => {
+            format!("Error: TemporarilyUnavailable, {}", message)
+        }
+        UpdateBalanceError::AlreadyProcessing => {
+            "Error: AlreadyProcessing, the minter is already processing a call for this account"
+                .to_string()
+        }
+        UpdateBalanceError::NoNewUtxos {
+            required_confirmations,
+            pending_utxos,
+            ..
+        } => {
+            let mut message = format!(
+                "Error: NoNewUtxos, deposits need {} confirmations",
+                required_confirmations
+            );
+            for utxo in pending_utxos.iter().flatten() {
+                message.push_str(&format!(
+                    "\nPending: {}:{} ({} BTC, {} confirmations)",
+                    format_txid(&utxo.outpoint.txid),
+                    utxo.outpoint.vout,
+                    format_e8s(utxo.value),
+                    utxo.confirmations
+                ));
+            }
+            message
+        }
+    }
+}
+
+fn render_retrieve_btc_error(error: &RetrieveBtcWithApprovalError) -> String {
+    match error {
+        RetrieveBtcWithApprovalError::MalformedAddress(message) => {
+            format!("Error: MalformedAddress, {}", message)
+        }
+        RetrieveBtcWithApprovalError::GenericError {
+            error_message,
+            error_code,
+        } => format!("Error: {} (code {})", error_message, error_code),
+        RetrieveBtcWithApprovalError::TemporarilyUnavailable(message) => {
+            format!("Error: TemporarilyUnavailable, {}", message)
+        }
+        RetrieveBtcWithApprovalError::InsufficientAllowance { allowance } => format!(
+            "Error: InsufficientAllowance, the minter may only spend {} BTC",
+            format_e8s(*allowance)
+        ),
+        RetrieveBtcWithApprovalError::AlreadyProcessing => {
+            "Error: AlreadyProcessing, the minter is already processing a call for this account"
+                .to_string()
+        }
+        RetrieveBtcWithApprovalError::AmountTooLow(minimum) => format!(
+            "Error: AmountTooLow, the minimum withdrawal is {} BTC",
+            format_e8s(*minimum)
+        ),
+        RetrieveBtcWithApprovalError::InsufficientFunds { balance } => format!(
+            "Error: InsufficientFunds, the balance is {} BTC",
+            format_e8s(*balance)
+        ),
+    }
+}
+
+fn render_status(status: &RetrieveBtcStatus) -> String {
+    match status {
+        RetrieveBtcStatus::Pending => {
+            "Pending: waiting to be included in a transaction".to_string()
+        }
+        RetrieveBtcStatus::Signing => "Signing: the transaction is being signed".to_string(),
+        RetrieveBtcStatus::Sending(Txid { txid }) => {
+            format!("Sending: transaction {}", format_txid(txid))
+        }
+        RetrieveBtcStatus::Submitted(Txid { txid }) => {
+            format!("Submitted: transaction {}", format_txid(txid))
+        }
+        RetrieveBtcStatus::Confirmed(Txid { txid }) => {
+            format!("Confirmed: transaction {}", format_txid(txid))
+        }
+        RetrieveBtcStatus::AmountTooLow => {
+            "AmountTooLow: the amount doesn't cover the Bitcoin fees".to_string()
+        }
+        RetrieveBtcStatus::Unknown => "Unknown: no withdrawal with this block index".to_string(),
+    }
+}
+
+/// Renders the reply of a ckBTC minter query, or returns `None` for other replies.
+pub fn render_reply(method_name: &str, blob: &[u8]) -> Option<String> {
+    match method_name {
+        "retrieve_btc_status" => Some(render_status(&Decode!(blob, RetrieveBtcStatus).ok()?)),
+        _ => None,
+    }
+}
+
+/// Describes the outcome of a call to the ckBTC minter.
+pub fn describe_update_reply(method_name: &str, blob: &[u8]) -> Option<Result<String, String>> {
+    match method_name {
+        "get_btc_address" => {
+            let address = Decode!(blob, String).ok()?;
+            Some(Ok(format!("Deposit address: {}", address)))
+        }
+        "update_balance" => match Decode!(blob, Result<Vec<UtxoStatus>, UpdateBalanceError>).ok()? {
+            Ok(statuses) if statuses.is_empty() => Some(Ok("OK".to_string())),
+            Ok(statuses) => Some(Ok(statuses
+                .iter()
+                .map(|status| match status {
+                    UtxoStatus::Minted {
+                        block_index,
+                        minted_amount,
+                        utxo,
+                    } => format!(
+                        "Minted {} ckBTC in block {} from {}",
+                        format_e8s(*minted_amount),
+                        block_index,
+                        format_utxo(utxo)
+                    ),
+                    UtxoStatus::Checked(utxo) => format!("Checked {}", format_utxo(utxo)),
+                    UtxoStatus::Tainted(utxo) => format!("Tainted {}", format_utxo(utxo)),
+                    UtxoStatus::ValueTooSmall(utxo) => {
+                        format!("Value too small {}", format_utxo(utxo))
+                    }
+                })
+                .collect::<Vec<_>>()
+                .join("\n"))),
+            Err(error) => Some(Err(render_update_balance_error(&error))),
+        },
+        "retrieve_btc_with_approval" => {
+            match Decode!(blob, Result<RetrieveBtcOk, RetrieveBtcWithApprovalError>).ok()? {
+                Ok(ok) => Some(Ok(format!(
+                    "Withdrawal accepted in block {}, check it with `ckbtc retrieve-btc-status --block-index {}`",
+                    ok.block_index, ok.block_index
+                ))),
+                Err(error) => Some(Err(render_retrieve_btc_error(&error))),
+            }
+        }
+        _ => None,
+    }
+}
@@ -0,0 +1,190 @@
+//! Parsing of human-friendly durations and timestamps used by governance commands.
+use crate::lib::AnyhowResult;
+use anyhow::anyhow;
+use chrono::{DateTime, TimeZone, Utc};
+use std::convert::TryFrom;
+use std::sync::atomic::{AtomicU64, Ordering};
+
+pub const ONE_DAY_SECONDS: u64 = 24 * 60 * 60;
+pub const ONE_YEAR_SECONDS: u64 = (4 * 365 + 1) * ONE_DAY_SECONDS / 4;
+pub const ONE_MONTH_SECONDS: u64 = ONE_YEAR_SECONDS / 12;
+
+/// The maximum dissolve delay a neuron can have (8 years).
+pub const MAX_DISSOLVE_DELAY_SECONDS: u64 = 8 * ONE_YEAR_SECONDS;
+
+/// The current time given with `--now`, or zero to use the clock of the machine.
+static NOW_OVERRIDE: AtomicU64 = AtomicU64::new(0);
+
+/// Overrides the current time, in seconds since the UNIX epoch, for machines whose
+/// clock isn't trusted.
+pub fn set_now(seconds: u64) {
+    NOW_OVERRIDE.store(seconds, Ordering::Relaxed);
+}
+
+/// Returns the current time in seconds since the UNIX epoch.
+pub fn now_seconds() -> u64 {
+    match NOW_OVERRIDE.load(Ordering::Relaxed) {
+        0 => system_now_seconds(),
+        now => now,
+    }
+}
+
+/// Returns the time of the clock of the machine, ignoring `--now`.
+pub fn system_now_seconds() -> u64 {
+    Utc::now().timestamp() as u64
+}
+
+/// The launch of the IC (2021-05-10): a clock showing an earlier time is certainly wrong.
+const IC_GENESIS_SECONDS: u64 = 1_620_604_800;
+
+/// Warns when the clock of the machine is off: when it differs from the time given with
+/// `--now` by more than a minute, or shows a time before the launch of the IC.
+pub fn warn_clock_skew() {
+    let system = system_now_seconds();
+    let now = now_seconds();
+    if now != system {
+        if system > now + 60 {
+            eprintln!(
+                "Warning: the clock of this machine is {} ahead of --now, messages expire relative to --now",
+                format_duration(system - now)
+            );
+        } else if now > system + 60 {
+            eprintln!(
+                "Warning: the clock of this machine is {} behind --now, messages expire relative to --now",
+                format_duration(now - system)
+            );
+        }
+    } else if system < IC_GENESIS_SECONDS {
+        eprintln!(
+            "Warning: the clock of this machine shows {}, which can't be right; give the current time with --now",
+            format_timestamp(system)
+        );
+    }
+}
+
+/// Parses a duration like `6m`, `1y 3d` or `90 days` into seconds.
+pub fn parse_duration(input: &str) -> AnyhowResult<u64> {
+    let input = input.trim();
+    if input.is_empty() {
+        return Err(anyhow!("Empty duration"));
+    }
+    let mut total: u64 = 0;
+    let mut chars = input.chars().peekable();
+    while chars.peek().is_some() {
+        while chars.peek().map_or(false, |c| c.is_whitespace()) {
+            chars.next();
+        }
+        let mut number = String::new();
+        while let Some(c) = chars.peek().filter(|c| c.is_ascii_digit() || **c == '_') {
+            number.push(*c);
+            chars.next();
+        }
+        while chars.peek().map_or(false, |c| c.is_whitespace()) {
+            chars.next();
+        }
+        let mut unit = String::new();
+        while let Some(c) = chars.peek().filter(|c| c.is_ascii_alphabetic()) {
+            unit.push(*c);
+            chars.next();
+        }
+        let number = number
+            .replace('_', "")
+            .parse::<u64>()
+            .map_err(|_| anyhow!("Couldn't parse the duration {:?}", input))?;
+        let multiplier = match unit.as_str() {
+            "y" | "yr" | "yrs" | "year" | "years" => ONE_YEAR_SECONDS,
+            "m" | "mo" | "month" | "months" => ONE_MONTH_SECONDS,
+            "w" | "week" | "weeks" => 7 * ONE_DAY_SECONDS,
+            "d" | "day" | "days" => ONE_DAY_SECONDS,
+            "h" | "hour" | "hours" => 60 * 60,
+            "min" | "mins" | "minute" | "minutes" => 60,
+            "" | "s" | "sec" | "secs" | "second" | "seconds" => 1,
+            unit => return Err(anyhow!("Unknown duration unit {:?}", unit)),
+        };
+        total = number
+            .checked_mul(multiplier)
+            .and_then(|v| total.checked_add(v))
+            .ok_or_else(|| anyhow!("The duration {:?} is too large", input))?;
+    }
+    Ok(total)
+}
+
+/// Parses a dissolve delay given as a duration (e.g. `6m`, `1y 3d`, `8y`) or as `max`,
+/// and checks that it is within the protocol bounds.
+pub fn parse_dissolve_delay(input: &str) -> AnyhowResult<u32> {
+    let seconds = match input.trim().to_lowercase().as_str() {
+        "max" | "to max" => MAX_DISSOLVE_DELAY_SECONDS,
+        _ => parse_duration(input)?,
+    };
+    if seconds == 0 {
+        return Err(anyhow!("The dissolve delay must be positive"));
+    }
+    if seconds > MAX_DISSOLVE_DELAY_SECONDS {
+        return Err(anyhow!(
+            "The dissolve delay {} exceeds the maximum of 8 years",
+            format_duration(seconds)
+        ));
+    }
+    Ok(seconds as u32)
+}
+
+/// Formats a number of seconds as a human-readable duration, e.g. `1 year 3 days`.
+pub fn format_duration(seconds: u64) -> String {
+    let units = [
+        ("year", ONE_YEAR_SECONDS),
+        ("day", ONE_DAY_SECONDS),
+        ("hour", 60 * 60),
+        ("minute", 60),
+        ("second", 1),
+    ];
+    let mut remaining = seconds;
+    let mut parts = Vec::new();
+    for (name, length) in units.iter() {
+        let count = remaining / length;
+        remaining %= length;
+        if count > 0 {
+            parts.push(format!(
+                "{} {}{}",
+                count,
+                name,
+                if count == 1 { "" } else { "s" }
+            ));
+        }
+    }
+    if parts.is_empty() {
+        return "0 seconds".to_string();
+    }
+    parts.join(" ")
+}
+
+/// Formats a UNIX timestamp in seconds as a UTC date, or as the raw number of seconds if
+/// it is out of the range of dates.
+pub fn format_timestamp(seconds: u64) -> String {
+    i64::try_from(seconds)
+        .ok()
+        .and_then(|seconds| Utc.timestamp_opt(seconds, 0).single())
+        .map_or_else(
+            || format!("{} seconds since the UNIX epoch", seconds),
+            |date| date.format("%Y-%m-%d %H:%M:%S UTC").to_string(),
+        )
+}
+
+/// Parses either an RFC 3339 date (e.g. `2025-05-01T00:00:00Z`) or a duration
+/// from now (e.g. `2y 6m`) into a UNIX timestamp in seconds.
+pub fn parse_timestamp(input: &str) -> AnyhowResult<u64> {
+    if let Ok(date) = DateTime::parse_from_rfc3339(input.trim()) {
+        if date.timestamp() < 0 {
+            return Err(anyhow!("The date {} is before the UNIX epoch", input));
+        }
+        return Ok(date.timestamp() as u64);
+    }
+    let duration = parse_duration(input).map_err(|_| {
+        anyhow!(
+            "Couldn't parse {:?} as an RFC 3339 date or a duration from now",
+            input
+        )
+    })?;
+    now_seconds()
+        .checked_add(duration)
+        .ok_or_else(|| anyhow!("The duration {:?} is too large", input))
+}
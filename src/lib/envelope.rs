@@ -0,0 +1,360 @@
+//! Verification of signed messages: the request id, the sender and the signature of
+//! every envelope are checked against its content.
+use crate::lib::{
+    signing::{Ingress, IngressWithRequestId, RequestStatus},
+    AnyhowResult,
+};
+use anyhow::anyhow;
+use ic_agent::Identity;
+use ic_types::Principal;
+use serde::Serialize;
+use serde_cbor::Value;
+use sha2::{Digest, Sha256};
+use std::collections::BTreeMap;
+use std::convert::TryFrom;
+
+/// The domain separator prepended to request ids before they are signed.
+const IC_REQUEST_DOMAIN_SEPARATOR: &[u8] = b"\x0Aic-request";
+
+/// The DER prefix of an Ed25519 public key, followed by the 32 bytes of the key.
+const ED25519_DER_PREFIX: &[u8] = &[
+    0x30, 0x2a, 0x30, 0x05, 0x06, 0x03, 0x2b, 0x65, 0x70, 0x03, 0x21, 0x00,
+];
+
+/// The DER prefix of a secp256k1 public key, followed by the 65 bytes of the
+/// uncompressed point.
+const SECP256K1_DER_PREFIX: &[u8] = &[
+    0x30, 0x56, 0x30, 0x10, 0x06, 0x07, 0x2a, 0x86, 0x48, 0xce, 0x3d, 0x02, 0x01, 0x06, 0x05, 0x2b,
+    0x81, 0x04, 0x00, 0x0a, 0x03, 0x42, 0x00,
+];
+
+/// A decoded envelope: the content of the request and the sender's key and signature.
+pub struct Envelope {
+    pub content: BTreeMap<String, Value>,
+    pub sender_pubkey: Option<Vec<u8>>,
+    pub sender_sig: Option<Vec<u8>>,
+}
+
+impl Envelope {
+    /// Decodes the hex-encoded CBOR envelope of a signed message.
+    pub fn decode(hex_content: &str) -> AnyhowResult<Envelope> {
+        let cbor: Value = serde_cbor::from_slice(&hex::decode(hex_content)?)
+            .map_err(|_| anyhow!("Invalid cbor data in the content of the message."))?;
+        let mut envelope = match cbor {
+            Value::Map(envelope) => envelope,
+            _ => return Err(anyhow!("Invalid cbor content")),
+        };
+        let bytes = |value: Option<Value>| match value {
+            Some(Value::Bytes(bytes)) => Ok(Some(bytes)),
+            None => Ok(None),
+            Some(_) => Err(anyhow!("Invalid cbor content")),
+        };
+        if envelope.contains_key(&Value::Text("sender_delegation".to_string())) {
+            return Err(anyhow!(
+                "Messages signed with a delegation can't be verified"
+            ));
+        }
+        let sender_pubkey = bytes(envelope.remove(&Value::Text("sender_pubkey".to_string())))?;
+        let sender_sig = bytes(envelope.remove(&Value::Text("sender_sig".to_string())))?;
+        let content = match envelope.remove(&Value::Text("content".to_string())) {
+            Some(Value::Map(content)) => content
+                .into_iter()
+                .map(|(key, value)| match key {
+                    Value::Text(key) => Ok((key, value)),
+                    _ => Err(anyhow!("Invalid cbor content")),
+                })
+                .collect::<AnyhowResult<_>>()?,
+            _ => return Err(anyhow!("Invalid cbor content")),
+        };
+        Ok(Envelope {
+            content,
+            sender_pubkey,
+            sender_sig,
+        })
+    }
+
+    /// Returns the request id: the representation-independent hash of the content.
+    pub fn request_id(&self) -> AnyhowResult<[u8; 32]> {
+        hash_map(
+            self.content
+                .iter()
+                .map(|(key, value)| (Value::Text(key.clone()), value.clone())),
+        )
+    }
+
+    /// Returns a field of the content holding bytes.
+    pub fn bytes(&self, field: &str) -> AnyhowResult<&[u8]> {
+        match self.content.get(field) {
+            Some(Value::Bytes(bytes)) => Ok(bytes),
+            _ => Err(anyhow!("The content has no {}", field)),
+        }
+    }
+
+    /// Returns the expiry of the message, in nanoseconds since the UNIX epoch.
+    pub fn ingress_expiry(&self) -> Option<u64> {
+        match self.content.get("ingress_expiry") {
+            Some(Value::Integer(expiry)) => u64::try_from(*expiry).ok(),
+            _ => None,
+        }
+    }
+
+    /// Returns the nonce of the message, if it has one.
+    pub fn nonce(&self) -> Option<&[u8]> {
+        self.bytes("nonce").ok()
+    }
+
+    /// Returns the paths read by a `read_state` request, as lists of labels.
+    pub fn paths(&self) -> Vec<Vec<Vec<u8>>> {
+        let labels = |path: &Value| match path {
+            Value::Array(labels) => labels
+                .iter()
+                .filter_map(|label| match label {
+                    Value::Bytes(label) => Some(label.clone()),
+                    _ => None,
+                })
+                .collect(),
+            _ => Vec::new(),
+        };
+        match self.content.get("paths") {
+            Some(Value::Array(paths)) => paths.iter().map(labels).collect(),
+            _ => Vec::new(),
+        }
+    }
+
+    /// Checks that the sender is derived from the public key and that the signature of
+    /// the request id is valid, and returns the request id. Anonymous messages carry
+    /// neither a key nor a signature.
+    pub fn verify(&self) -> AnyhowResult<[u8; 32]> {
+        let request_id = self.request_id()?;
+        let sender = Principal::try_from(self.bytes("sender")?)?;
+        let (pubkey, sig) = match (&self.sender_pubkey, &self.sender_sig) {
+            (None, None) if sender == Principal::anonymous() => return Ok(request_id),
+            (Some(pubkey), Some(sig)) => (pubkey, sig),
+            _ => return Err(anyhow!("The message isn't signed")),
+        };
+        if Principal::self_authenticating(pubkey) != sender {
+            return Err(anyhow!(
+                "The sender {} isn't derived from the public key of the message",
+                sender
+            ));
+        }
+        let mut message = IC_REQUEST_DOMAIN_SEPARATOR.to_vec();
+        message.extend_from_slice(&request_id);
+        if let Some(key) = pubkey.strip_prefix(ED25519_DER_PREFIX) {
+            ring::signature::UnparsedPublicKey::new(&ring::signature::ED25519, key)
+                .verify(&message, sig)
+                .map_err(|_| anyhow!("The signature of the message is invalid"))?;
+        } else if let Some(key) = pubkey.strip_prefix(SECP256K1_DER_PREFIX) {
+            let key = libsecp256k1::PublicKey::parse_slice(key, None)
+                .map_err(|err| anyhow!("Invalid secp256k1 public key: {:?}", err))?;
+            let mut sig = libsecp256k1::Signature::parse_standard_slice(sig)
+                .map_err(|err| anyhow!("Invalid secp256k1 signature: {:?}", err))?;
+            sig.normalize_s();
+            let digest = libsecp256k1::Message::parse(&sha256(&message));
+            if !libsecp256k1::verify(&digest, &sig, &key) {
+                return Err(anyhow!("The signature of the message is invalid"));
+            }
+        } else {
+            return Err(anyhow!("Unsupported public key type"));
+        }
+        Ok(request_id)
+    }
+}
+
+/// Signs a `read_state` request for the status of the request, valid until the given
+/// expiry in nanoseconds since the UNIX epoch, and returns its CBOR envelope.
+pub fn sign_request_status(
+    identity: &dyn Identity,
+    request_id: &[u8],
+    expiry: u64,
+) -> AnyhowResult<Vec<u8>> {
+    let sender = identity.sender().map_err(|err| anyhow!(err))?;
+    let mut content = BTreeMap::new();
+    content.insert(
+        "request_type".to_string(),
+        Value::Text("read_state".to_string()),
+    );
+    content.insert(
+        "sender".to_string(),
+        Value::Bytes(sender.as_slice().to_vec()),
+    );
+    content.insert(
+        "paths".to_string(),
+        Value::Array(vec![Value::Array(vec![
+            Value::Bytes(b"request_status".to_vec()),
+            Value::Bytes(request_id.to_vec()),
+        ])]),
+    );
+    content.insert("ingress_expiry".to_string(), Value::Integer(expiry.into()));
+    let envelope = Envelope {
+        content,
+        sender_pubkey: None,
+        sender_sig: None,
+    };
+    let mut message = IC_REQUEST_DOMAIN_SEPARATOR.to_vec();
+    message.extend_from_slice(&envelope.request_id()?);
+    let signature = identity.sign(&message).map_err(|err| anyhow!(err))?;
+
+    let mut cbor = BTreeMap::new();
+    cbor.insert(
+        Value::Text("content".to_string()),
+        Value::Map(
+            envelope
+                .content
+                .into_iter()
+                .map(|(key, value)| (Value::Text(key), value))
+                .collect(),
+        ),
+    );
+    if let Some(pubkey) = signature.public_key {
+        cbor.insert(
+            Value::Text("sender_pubkey".to_string()),
+            Value::Bytes(pubkey),
+        );
+    }
+    if let Some(sig) = signature.signature {
+        cbor.insert(Value::Text("sender_sig".to_string()), Value::Bytes(sig));
+    }
+    let mut serializer = serde_cbor::Serializer::new(Vec::new());
+    serializer.self_describe()?;
+    Value::Map(cbor).serialize(&mut serializer)?;
+    Ok(serializer.into_inner())
+}
+
+/// Verifies a message and its copies for later validity windows.
+pub fn verify_ingress(message: &Ingress) -> AnyhowResult {
+    verify_copy(message)?;
+    for copy in &message.later_copies {
+        verify_copy(copy)?;
+        check_same_call(&message.content, &copy.content)?;
+    }
+    Ok(())
+}
+
+/// Verifies a message and its request status queries, and its copies for later validity
+/// windows: all envelopes must be valid, and every query must read the status of its
+/// very message, from the same canister.
+pub fn verify_ingress_with_request_id(message: &IngressWithRequestId) -> AnyhowResult {
+    for copy in std::iter::once(message).chain(&message.later_copies) {
+        let request_id = verify_copy(&copy.ingress)?;
+        let canister_id =
+            Principal::try_from(Envelope::decode(&copy.ingress.content)?.bytes("canister_id")?)?;
+        for status in copy.status_queries() {
+            verify_request_status(&status, request_id, canister_id)?;
+        }
+        check_same_call(&message.ingress.content, &copy.ingress.content)?;
+    }
+    Ok(())
+}
+
+/// Verifies the envelope of a single copy of a message, and that the request id declared
+/// next to it matches its content.
+fn verify_copy(message: &Ingress) -> AnyhowResult<[u8; 32]> {
+    let request_id = Envelope::decode(&message.content)?.verify()?;
+    if let Some(declared) = &message.request_id {
+        if hex::decode(declared)? != request_id {
+            return Err(anyhow!(
+                "The declared request id {} doesn't match the content of the message",
+                declared
+            ));
+        }
+    }
+    Ok(request_id)
+}
+
+/// Checks that a copy of a message makes the same call, only with another expiry.
+fn check_same_call(message: &str, copy: &str) -> AnyhowResult {
+    let call = |content: &str| -> AnyhowResult<_> {
+        let mut content = Envelope::decode(content)?.content;
+        content.remove("ingress_expiry");
+        content.remove("nonce");
+        Ok(content)
+    };
+    if call(message)? != call(copy)? {
+        return Err(anyhow!(
+            "A copy of the message for a later window makes a different call"
+        ));
+    }
+    Ok(())
+}
+
+fn verify_request_status(
+    status: &RequestStatus,
+    request_id: [u8; 32],
+    canister_id: Principal,
+) -> AnyhowResult {
+    let envelope = Envelope::decode(&status.content)?;
+    envelope.verify()?;
+    if hex::decode(&status.request_id)? != request_id {
+        return Err(anyhow!(
+            "The request status query is for request {}, not for the message",
+            status.request_id
+        ));
+    }
+    if Principal::from_text(&status.canister_id)? != canister_id {
+        return Err(anyhow!(
+            "The request status query is addressed to {}, not to the canister of the message",
+            status.canister_id
+        ));
+    }
+    let path = Value::Array(vec![
+        Value::Bytes(b"request_status".to_vec()),
+        Value::Bytes(request_id.to_vec()),
+    ]);
+    match envelope.content.get("paths") {
+        Some(Value::Array(paths)) if paths.contains(&path) => Ok(()),
+        _ => Err(anyhow!(
+            "The request status query doesn't read the status of the message"
+        )),
+    }
+}
+
+fn sha256(data: &[u8]) -> [u8; 32] {
+    let mut hash = [0; 32];
+    hash.copy_from_slice(&Sha256::digest(data));
+    hash
+}
+
+/// Hashes a map as defined by the representation-independent hashing of the IC: the
+/// sorted hashes of the key-value pairs are concatenated and hashed.
+fn hash_map(entries: impl Iterator<Item = (Value, Value)>) -> AnyhowResult<[u8; 32]> {
+    let mut pairs = Vec::new();
+    for (key, value) in entries {
+        let mut pair = hash_value(&key)?.to_vec();
+        pair.extend_from_slice(&hash_value(&value)?);
+        pairs.push(pair);
+    }
+    pairs.sort();
+    Ok(sha256(&pairs.concat()))
+}
+
+fn hash_value(value: &Value) -> AnyhowResult<[u8; 32]> {
+    Ok(match value {
+        Value::Text(text) => sha256(text.as_bytes()),
+        Value::Bytes(bytes) => sha256(bytes),
+        Value::Integer(number) => {
+            let mut number =
+                u128::try_from(*number).map_err(|_| anyhow!("Negative numbers can't be hashed"))?;
+            let mut leb128 = Vec::new();
+            loop {
+                let byte = (number & 0x7f) as u8;
+                number >>= 7;
+                if number == 0 {
+                    leb128.push(byte);
+                    break;
+                }
+                leb128.push(byte | 0x80);
+            }
+            sha256(&leb128)
+        }
+        Value::Array(values) => sha256(
+            &values
+                .iter()
+                .map(hash_value)
+                .collect::<AnyhowResult<Vec<_>>>()?
+                .concat(),
+        ),
+        Value::Map(entries) => hash_map(entries.clone().into_iter())?,
+        _ => return Err(anyhow!("Unsupported value in the content of the message")),
+    })
+}
@@ -0,0 +1,966 @@
+//! Helpers for presenting NNS governance data.
+use crate::lib::duration::{format_duration, format_timestamp, now_seconds};
+use crate::lib::{governance_canister_id, AnyhowResult};
+use anyhow::anyhow;
+use candid::{CandidType, Decode, Deserialize, Principal};
+use ic_nns_common::pb::v1::{NeuronId, ProposalId};
+use ic_nns_governance::pb::v1::{
+    claim_or_refresh_neuron_from_account_response::Result as ClaimOrRefreshResult,
+    manage_neuron::{configure::Operation, Command, Configure, NeuronIdOrSubaccount},
+    ClaimOrRefreshNeuronFromAccountResponse, ManageNeuron,
+};
+use std::convert::TryFrom;
+
+/// The subset of the governance `Neuron` record shown in reports.
+#[derive(CandidType, Deserialize, Clone)]
+pub struct Neuron {
+    pub id: Option<NeuronId>,
+    pub controller: Option<Principal>,
+    pub recent_ballots: Vec<BallotInfo>,
+    pub maturity_e8s_equivalent: u64,
+    pub cached_neuron_stake_e8s: u64,
+    pub neuron_fees_e8s: u64,
+    pub created_timestamp_seconds: u64,
+    pub aging_since_timestamp_seconds: u64,
+    pub hot_keys: Vec<Principal>,
+    pub dissolve_state: Option<DissolveState>,
+    pub followees: Vec<(i32, Followees)>,
+    pub staked_maturity_e8s_equivalent: Option<u64>,
+    pub auto_stake_maturity: Option<bool>,
+    pub visibility: Option<i32>,
+    pub voting_power_refreshed_timestamp_seconds: Option<u64>,
+}
+
+#[derive(CandidType, Deserialize, Clone)]
+pub struct NeuronInfo {
+    pub dissolve_delay_seconds: u64,
+    pub recent_ballots: Vec<BallotInfo>,
+    pub created_timestamp_seconds: u64,
+    pub state: i32,
+    pub retrieved_at_timestamp_seconds: u64,
+    pub voting_power: u64,
+    pub age_seconds: u64,
+    pub visibility: Option<i32>,
+}
+
+#[derive(CandidType, Deserialize, Clone)]
+pub struct BallotInfo {
+    pub vote: i32,
+    pub proposal_id: Option<ProposalId>,
+}
+
+#[derive(CandidType, Deserialize, Clone)]
+pub enum DissolveState {
+    DissolveDelaySeconds(u64),
+    WhenDissolvedTimestampSeconds(u64),
+}
+
+#[derive(CandidType, Deserialize, Clone)]
+pub struct Followees {
+    pub followees: Vec<NeuronId>,
+}
+
+#[derive(CandidType, Deserialize)]
+pub struct ListNeuronsResponse {
+    pub neuron_infos: Vec<(u64, NeuronInfo)>,
+    pub full_neurons: Vec<Neuron>,
+    pub total_pages_available: Option<u64>,
+}
+
+/// The `list_neurons` argument, including the fields added after the pinned
+/// `ic-nns-governance` revision.
+#[derive(CandidType)]
+pub struct ListNeurons {
+    pub neuron_ids: Vec<u64>,
+    pub include_neurons_readable_by_caller: bool,
+    pub include_empty_neurons_readable_by_caller: Option<bool>,
+    pub include_public_neurons_in_full_neurons: Option<bool>,
+    pub page_number: Option<u64>,
+    pub page_size: Option<u64>,
+}
+
+/// A `manage_neuron` argument for the commands added to governance after the pinned
+/// `ic-nns-governance` revision, which only knows the older commands.
+#[derive(CandidType)]
+pub struct ManageNeuronArgs {
+    pub id: Option<NeuronId>,
+    pub command: Option<NeuronCommand>,
+    pub neuron_id_or_subaccount: Option<NeuronIdOrSubaccount>,
+}
+
+/// The neuron addressed by a `manage_neuron` argument, which can be decoded whatever
+/// the command is.
+#[derive(CandidType, Deserialize)]
+pub struct ManageNeuronTarget {
+    pub id: Option<NeuronId>,
+    pub neuron_id_or_subaccount: Option<NeuronIdOrSubaccount>,
+}
+
+#[derive(CandidType)]
+pub enum NeuronCommand {
+    Configure(ConfigureArgs),
+    StakeMaturity(StakeMaturity),
+    DisburseMaturity(DisburseMaturity),
+    RefreshVotingPower(Empty),
+}
+
+#[derive(CandidType)]
+pub struct ConfigureArgs {
+    pub operation: Option<ConfigureOperation>,
+}
+
+#[derive(CandidType)]
+pub enum ConfigureOperation {
+    ChangeAutoStakeMaturity(ChangeAutoStakeMaturity),
+    SetVisibility(SetVisibility),
+}
+
+#[derive(CandidType)]
+pub struct ChangeAutoStakeMaturity {
+    pub requested_setting_for_auto_stake_maturity: bool,
+}
+
+#[derive(CandidType)]
+pub struct SetVisibility {
+    pub visibility: Option<i32>,
+}
+
+#[derive(CandidType)]
+pub struct StakeMaturity {
+    pub percentage_to_stake: Option<u32>,
+}
+
+#[derive(CandidType)]
+pub struct DisburseMaturity {
+    pub percentage_to_disburse: u32,
+    pub to_account: Option<Account>,
+}
+
+#[derive(CandidType)]
+pub struct Account {
+    pub owner: Option<Principal>,
+    pub subaccount: Option<Vec<u8>>,
+}
+
+#[derive(CandidType, Deserialize)]
+pub struct Empty {}
+
+/// The reply of `manage_neuron`, covering the commands of the current governance API.
+#[derive(CandidType, Deserialize)]
+pub struct ManageNeuronResponse {
+    pub command: Option<CommandResponse>,
+}
+
+#[derive(CandidType, Deserialize)]
+pub enum CommandResponse {
+    Error(GovernanceError),
+    Spawn(SpawnResponse),
+    Split(SpawnResponse),
+    Follow(Empty),
+    ClaimOrRefresh(ClaimOrRefreshResponse),
+    Configure(Empty),
+    RegisterVote(Empty),
+    Merge(Empty),
+    DisburseToNeuron(SpawnResponse),
+    MakeProposal(MakeProposalResponse),
+    MergeMaturity(MergeMaturityResponse),
+    Disburse(DisburseResponse),
+    StakeMaturity(StakeMaturityResponse),
+    DisburseMaturity(DisburseMaturityResponse),
+    RefreshVotingPower(Empty),
+}
+
+#[derive(CandidType, Deserialize)]
+pub struct SpawnResponse {
+    pub created_neuron_id: Option<NeuronId>,
+}
+
+#[derive(CandidType, Deserialize)]
+pub struct ClaimOrRefreshResponse {
+    pub refreshed_neuron_id: Option<NeuronId>,
+}
+
+#[derive(CandidType, Deserialize)]
+pub struct MakeProposalResponse {
+    pub proposal_id: Option<ProposalId>,
+}
+
+#[derive(CandidType, Deserialize)]
+pub struct MergeMaturityResponse {
+    pub merged_maturity_e8s: u64,
+    pub new_stake_e8s: u64,
+}
+
+#[derive(CandidType, Deserialize)]
+pub struct DisburseResponse {
+    pub transfer_block_height: u64,
+}
+
+#[derive(CandidType, Deserialize)]
+pub struct StakeMaturityResponse {
+    pub maturity_e8s: u64,
+    pub staked_maturity_e8s: u64,
+}
+
+#[derive(CandidType, Deserialize)]
+pub struct DisburseMaturityResponse {
+    pub amount_disbursed_e8s: Option<u64>,
+}
+
+#[derive(CandidType, Deserialize)]
+pub struct ListProposalInfo {
+    pub include_reward_status: Vec<i32>,
+    pub before_proposal: Option<ProposalId>,
+    pub limit: u32,
+    pub exclude_topic: Vec<i32>,
+    pub include_status: Vec<i32>,
+}
+
+#[derive(CandidType, Deserialize)]
+pub struct ListProposalInfoResponse {
+    pub proposal_info: Vec<ProposalInfo>,
+}
+
+/// The subset of the governance `ProposalInfo` record shown to voters.
+#[derive(CandidType, Deserialize)]
+pub struct ProposalInfo {
+    pub id: Option<ProposalId>,
+    pub status: i32,
+    pub topic: i32,
+    pub failure_reason: Option<GovernanceError>,
+    pub proposal_timestamp_seconds: u64,
+    pub latest_tally: Option<Tally>,
+    pub reward_status: i32,
+    pub decided_timestamp_seconds: u64,
+    pub deadline_timestamp_seconds: Option<u64>,
+    pub proposal: Option<Proposal>,
+    pub proposer: Option<NeuronId>,
+    pub executed_timestamp_seconds: u64,
+}
+
+#[derive(CandidType, Deserialize)]
+pub struct Proposal {
+    pub title: Option<String>,
+    pub url: String,
+    pub summary: String,
+}
+
+#[derive(CandidType, Deserialize)]
+pub struct Tally {
+    pub no: u64,
+    pub yes: u64,
+    pub total: u64,
+    pub timestamp_seconds: u64,
+}
+
+#[derive(CandidType, Deserialize)]
+pub struct GovernanceError {
+    pub error_message: String,
+    pub error_type: i32,
+}
+
+/// Formats an amount of e8s as ICP with all 8 decimal digits.
+pub fn format_e8s(e8s: u64) -> String {
+    format!("{}.{:08}", e8s / 100_000_000, e8s % 100_000_000)
+}
+
+/// Returns the name of a ballot's vote.
+pub fn vote_name(vote: i32) -> String {
+    match vote {
+        0 => "Unspecified".to_string(),
+        1 => "Yes".to_string(),
+        2 => "No".to_string(),
+        vote => format!("Vote{}", vote),
+    }
+}
+
+/// Returns the name of a neuron state as reported in `NeuronInfo`.
+pub fn neuron_state_name(state: i32) -> String {
+    match state {
+        0 => "Unspecified".to_string(),
+        1 => "Locked".to_string(),
+        2 => "Dissolving".to_string(),
+        3 => "Dissolved".to_string(),
+        4 => "Spawning".to_string(),
+        state => format!("State{}", state),
+    }
+}
+
+/// Describes the dissolve state of a neuron, as a remaining delay or a date.
+pub fn describe_dissolve_state(dissolve_state: &Option<DissolveState>) -> String {
+    match dissolve_state {
+        Some(DissolveState::DissolveDelaySeconds(0)) | None => "Dissolved".to_string(),
+        Some(DissolveState::DissolveDelaySeconds(delay)) => {
+            format!("Locked, dissolve delay {}", format_duration(*delay))
+        }
+        Some(DissolveState::WhenDissolvedTimestampSeconds(timestamp)) => {
+            if *timestamp <= now_seconds() {
+                "Dissolved".to_string()
+            } else {
+                format!("Dissolving, dissolved on {}", format_timestamp(*timestamp))
+            }
+        }
+    }
+}
+
+/// Proposal topics, indexed by their numeric value.
+const TOPICS: &[&str] = &[
+    "Unspecified",
+    "NeuronManagement",
+    "ExchangeRate",
+    "NetworkEconomics",
+    "Governance",
+    "NodeAdmin",
+    "ParticipantManagement",
+    "SubnetManagement",
+    "NetworkCanisterManagement",
+    "Kyc",
+    "NodeProviderRewards",
+    "SnsDecentralizationSale",
+    "IcOsVersionDeployment",
+    "IcOsVersionElection",
+    "SnsAndCommunityFund",
+    "ApiBoundaryNodeManagement",
+    "SubnetRental",
+    "ProtocolCanisterManagement",
+    "ServiceNervousSystemManagement",
+];
+
+/// Returns the name of a proposal topic.
+pub fn topic_name(topic: i32) -> String {
+    lookup_name(TOPICS, topic, "Topic")
+}
+
+/// Parses a proposal topic given either by name (e.g. `governance`, `node-admin`) or by number.
+pub fn parse_topic(topic: &str) -> Option<i32> {
+    parse_name(TOPICS, topic)
+}
+
+/// Proposal statuses, indexed by their numeric value.
+const PROPOSAL_STATUSES: &[&str] = &[
+    "Unspecified",
+    "Open",
+    "Rejected",
+    "Adopted",
+    "Executed",
+    "Failed",
+];
+
+/// Proposal reward statuses, indexed by their numeric value.
+const REWARD_STATUSES: &[&str] = &[
+    "Unspecified",
+    "AcceptVotes",
+    "ReadyToSettle",
+    "Settled",
+    "Ineligible",
+];
+
+/// Neuron visibilities, indexed by their numeric value.
+const VISIBILITIES: &[&str] = &["Unspecified", "Private", "Public"];
+
+/// Returns the name of a neuron visibility.
+pub fn visibility_name(visibility: i32) -> String {
+    lookup_name(VISIBILITIES, visibility, "Visibility")
+}
+
+/// Parses a neuron visibility given either by name (`private` or `public`) or by number
+/// (1 or 2). Unspecified and unknown visibilities are refused.
+pub fn parse_visibility(visibility: &str) -> Option<i32> {
+    parse_name(VISIBILITIES, visibility)
+        .filter(|visibility| (1..VISIBILITIES.len() as i32).contains(visibility))
+}
+
+/// Returns all known proposal topics.
+pub fn all_topics() -> Vec<i32> {
+    (0..TOPICS.len() as i32).collect()
+}
+
+/// Returns the name of a proposal status.
+pub fn proposal_status_name(status: i32) -> String {
+    lookup_name(PROPOSAL_STATUSES, status, "Status")
+}
+
+/// Parses a proposal status given either by name (e.g. `open`) or by number.
+pub fn parse_proposal_status(status: &str) -> Option<i32> {
+    parse_name(PROPOSAL_STATUSES, status)
+}
+
+/// Returns the name of a proposal reward status.
+pub fn reward_status_name(status: i32) -> String {
+    lookup_name(REWARD_STATUSES, status, "RewardStatus")
+}
+
+fn lookup_name(names: &[&str], value: i32, prefix: &str) -> String {
+    usize::try_from(value)
+        .ok()
+        .and_then(|index| names.get(index))
+        .map(|name| name.to_string())
+        .unwrap_or_else(|| format!("{}{}", prefix, value))
+}
+
+fn parse_name(names: &[&str], name: &str) -> Option<i32> {
+    if let Ok(number) = name.parse::<i32>() {
+        return Some(number);
+    }
+    let normalized = name.replace(&['-', '_', ' '][..], "").to_lowercase();
+    names
+        .iter()
+        .position(|name| name.to_lowercase() == normalized)
+        .map(|index| index as i32)
+}
+
+/// Renders a proposal for voters; the summary is shortened unless `full` is set.
+pub fn render_proposal(info: &ProposalInfo, full: bool) -> String {
+    let mut lines = Vec::new();
+    let id = info
+        .id
+        .as_ref()
+        .map_or_else(|| "-".to_string(), |id| id.id.to_string());
+    let (title, url, summary) = match &info.proposal {
+        Some(proposal) => (
+            proposal.title.clone().unwrap_or_else(|| {
+                proposal
+                    .summary
+                    .lines()
+                    .next()
+                    .unwrap_or_default()
+                    .to_string()
+            }),
+            proposal.url.clone(),
+            proposal.summary.clone(),
+        ),
+        None => Default::default(),
+    };
+    lines.push(format!("Proposal {}: {}", id, title));
+    lines.push(format!("  Topic:     {}", topic_name(info.topic)));
+    lines.push(format!(
+        "  Status:    {} (rewards: {})",
+        proposal_status_name(info.status),
+        reward_status_name(info.reward_status)
+    ));
+    if let Some(proposer) = &info.proposer {
+        lines.push(format!("  Proposer:  neuron {}", proposer.id));
+    }
+    lines.push(format!(
+        "  Created:   {}",
+        format_timestamp(info.proposal_timestamp_seconds)
+    ));
+    if let Some(deadline) = info.deadline_timestamp_seconds {
+        let now = now_seconds();
+        if deadline > now {
+            lines.push(format!(
+                "  Deadline:  {} (in {})",
+                format_timestamp(deadline),
+                format_duration(deadline - now)
+            ));
+        } else {
+            lines.push(format!("  Deadline:  {}", format_timestamp(deadline)));
+        }
+    }
+    if info.decided_timestamp_seconds > 0 {
+        lines.push(format!(
+            "  Decided:   {}",
+            format_timestamp(info.decided_timestamp_seconds)
+        ));
+    }
+    if info.executed_timestamp_seconds > 0 {
+        lines.push(format!(
+            "  Executed:  {}",
+            format_timestamp(info.executed_timestamp_seconds)
+        ));
+    }
+    if let Some(tally) = &info.latest_tally {
+        let percent = |votes: u64| {
+            if tally.total == 0 {
+                0.0
+            } else {
+                votes as f64 * 100.0 / tally.total as f64
+            }
+        };
+        lines.push(format!(
+            "  Tally:     Yes {:.2}%, No {:.2}% of {} ICP voting power",
+            percent(tally.yes),
+            percent(tally.no),
+            format_e8s(tally.total)
+        ));
+    }
+    if let Some(error) = &info.failure_reason {
+        lines.push(format!("  Failure:   {}", error.error_message));
+    }
+    if !url.is_empty() {
+        lines.push(format!("  URL:       {}", url));
+    }
+    if !summary.is_empty() {
+        let summary = if full || summary.chars().count() <= SHORT_SUMMARY_LENGTH {
+            summary
+        } else {
+            format!(
+                "{}...",
+                summary
+                    .chars()
+                    .take(SHORT_SUMMARY_LENGTH)
+                    .collect::<String>()
+            )
+        };
+        lines.push("  Summary:".to_string());
+        lines.extend(summary.lines().map(|line| format!("    {}", line)));
+    }
+    lines.join("\n")
+}
+
+/// The number of characters of a proposal summary shown in proposal lists.
+const SHORT_SUMMARY_LENGTH: usize = 300;
+
+/// Renders governance replies that have a dedicated readable form. Returns `None` for
+/// all other replies, which are shown as candid.
+pub fn render_reply(
+    canister_id: Principal,
+    method_name: &str,
+    blob: &[u8],
+) -> AnyhowResult<Option<String>> {
+    if canister_id != governance_canister_id() {
+        return Ok(None);
+    }
+    let decoding_error = |err| anyhow!("Couldn't decode the {} reply: {}", method_name, err);
+    let rendered = match method_name {
+        "list_proposals" => {
+            let response = Decode!(blob, ListProposalInfoResponse).map_err(decoding_error)?;
+            render_proposals(&response.proposal_info)
+        }
+        "get_pending_proposals" => {
+            render_proposals(&Decode!(blob, Vec<ProposalInfo>).map_err(decoding_error)?)
+        }
+        "get_proposal_info" => match Decode!(blob, Option<ProposalInfo>).map_err(decoding_error)? {
+            Some(info) => render_proposal(&info, true),
+            None => "Proposal not found".to_string(),
+        },
+        "get_neuron_info" => {
+            match Decode!(blob, Result<NeuronInfo, GovernanceError>).map_err(decoding_error)? {
+                Ok(info) => render_neuron_info(&info),
+                Err(error) => render_error(&error),
+            }
+        }
+        "get_full_neuron" => {
+            match Decode!(blob, Result<Neuron, GovernanceError>).map_err(decoding_error)? {
+                Ok(neuron) => render_neuron(&neuron),
+                Err(error) => render_error(&error),
+            }
+        }
+        "get_neuron_ids" => {
+            let ids = Decode!(blob, Vec<u64>).map_err(decoding_error)?;
+            if ids.is_empty() {
+                "No neurons found".to_string()
+            } else {
+                ids.iter()
+                    .map(|id| id.to_string())
+                    .collect::<Vec<_>>()
+                    .join("\n")
+            }
+        }
+        _ => return Ok(None),
+    };
+    Ok(Some(rendered))
+}
+
+/// Renders the public information about a neuron.
+pub fn render_neuron_info(info: &NeuronInfo) -> String {
+    let mut lines = vec![
+        format!("State:          {}", neuron_state_name(info.state)),
+        format!(
+            "Dissolve delay: {}",
+            format_duration(info.dissolve_delay_seconds)
+        ),
+        format!("Voting power:   {} ICP", format_e8s(info.voting_power)),
+        format!("Age:            {}", format_duration(info.age_seconds)),
+        format!(
+            "Visibility:     {}",
+            visibility_name(info.visibility.unwrap_or_default())
+        ),
+        format!(
+            "Created:        {}",
+            format_timestamp(info.created_timestamp_seconds)
+        ),
+        format!(
+            "Retrieved at:   {}",
+            format_timestamp(info.retrieved_at_timestamp_seconds)
+        ),
+    ];
+    lines.extend(render_ballots(&info.recent_ballots));
+    lines.join("\n")
+}
+
+/// Renders the full neuron, as seen by its controller or hot keys.
+pub fn render_neuron(neuron: &Neuron) -> String {
+    let now = now_seconds();
+    let mut lines = vec![
+        format!(
+            "Neuron id:      {}",
+            neuron
+                .id
+                .as_ref()
+                .map_or_else(|| "-".to_string(), |id| id.id.to_string())
+        ),
+        format!(
+            "Controller:     {}",
+            neuron
+                .controller
+                .as_ref()
+                .map_or_else(|| "-".to_string(), |principal| principal.to_string())
+        ),
+        format!(
+            "Stake:          {} ICP",
+            format_e8s(
+                neuron
+                    .cached_neuron_stake_e8s
+                    .saturating_sub(neuron.neuron_fees_e8s)
+            )
+        ),
+        format!(
+            "Maturity:       {} ICP",
+            format_e8s(neuron.maturity_e8s_equivalent)
+        ),
+        format!(
+            "Staked maturity: {} ICP{}",
+            format_e8s(neuron.staked_maturity_e8s_equivalent.unwrap_or_default()),
+            if neuron.auto_stake_maturity == Some(true) {
+                " (auto-stake)"
+            } else {
+                ""
+            }
+        ),
+        format!(
+            "Dissolve state: {}",
+            describe_dissolve_state(&neuron.dissolve_state)
+        ),
+        format!(
+            "Age:            {}",
+            if neuron.aging_since_timestamp_seconds < now {
+                format_duration(now - neuron.aging_since_timestamp_seconds)
+            } else {
+                "-".to_string()
+            }
+        ),
+        format!(
+            "Created:        {}",
+            format_timestamp(neuron.created_timestamp_seconds)
+        ),
+    ];
+    if let Some(visibility) = neuron.visibility {
+        lines.push(format!("Visibility:     {}", visibility_name(visibility)));
+    }
+    if let Some(timestamp) = neuron.voting_power_refreshed_timestamp_seconds {
+        lines.push(format!(
+            "Voting power refreshed: {}",
+            format_timestamp(timestamp)
+        ));
+    }
+    for hot_key in &neuron.hot_keys {
+        lines.push(format!("Hot key:        {}", hot_key));
+    }
+    let mut followees = neuron.followees.clone();
+    followees.sort_by_key(|(topic, _)| *topic);
+    for (topic, followees) in followees {
+        lines.push(format!(
+            "Follows on {}: {}",
+            topic_name(topic),
+            followees
+                .followees
+                .iter()
+                .map(|id| id.id.to_string())
+                .collect::<Vec<_>>()
+                .join(", ")
+        ));
+    }
+    lines.extend(render_ballots(&neuron.recent_ballots));
+    lines.join("\n")
+}
+
+fn render_ballots(ballots: &[BallotInfo]) -> Vec<String> {
+    ballots
+        .iter()
+        .map(|ballot| {
+            format!(
+                "Voted:          {} on proposal {}",
+                vote_name(ballot.vote),
+                ballot
+                    .proposal_id
+                    .as_ref()
+                    .map_or_else(|| "-".to_string(), |id| id.id.to_string())
+            )
+        })
+        .collect()
+}
+
+/// Renders an error returned by the governance canister.
+pub fn render_error(error: &GovernanceError) -> String {
+    let (name, explanation) = usize::try_from(error.error_type)
+        .ok()
+        .and_then(|index| ERROR_TYPES.get(index))
+        .copied()
+        .unwrap_or((
+            "Unknown",
+            "The error type is not known to this version of quill.",
+        ));
+    format!(
+        "Error: {} (type {}): {}\n{}",
+        name, error.error_type, error.error_message, explanation
+    )
+}
+
+/// Names and explanations of the governance error types, indexed by `error_type`.
+const ERROR_TYPES: &[(&str, &str)] = &[
+    ("Unspecified", "The governance canister didn't specify the error."),
+    ("Ok", "The operation succeeded."),
+    (
+        "Unavailable",
+        "The governance canister is temporarily unavailable, try again later.",
+    ),
+    (
+        "NotAuthorized",
+        "The signer is neither the controller of the neuron nor allowed to perform this operation as a hot key.",
+    ),
+    ("NotFound", "The neuron or proposal doesn't exist."),
+    (
+        "InvalidCommand",
+        "The command is malformed or not applicable to the neuron.",
+    ),
+    (
+        "RequiresNotDissolving",
+        "The neuron must not be dissolving, stop dissolving it first.",
+    ),
+    (
+        "RequiresDissolving",
+        "The neuron must be dissolving, start dissolving it first.",
+    ),
+    (
+        "RequiresDissolved",
+        "The neuron must be fully dissolved, wait until its dissolve delay has passed.",
+    ),
+    (
+        "HotKey",
+        "The hot key is already added to the neuron or isn't one of its hot keys.",
+    ),
+    (
+        "ResourceExhausted",
+        "A governance limit was reached, e.g. the maximum number of neurons or hot keys.",
+    ),
+    (
+        "PreconditionFailed",
+        "A precondition of the operation doesn't hold, e.g. the resulting stake would be below the minimum or the dissolve delay is too short.",
+    ),
+    (
+        "External",
+        "A call from the governance canister to another canister, such as the ledger, failed.",
+    ),
+    (
+        "LedgerUpdateOngoing",
+        "Another operation on this neuron is updating the ledger, try again later.",
+    ),
+    (
+        "InsufficientFunds",
+        "The neuron's stake or maturity is too small for this operation.",
+    ),
+    ("InvalidPrincipal", "The principal is invalid."),
+    ("InvalidProposal", "The proposal is invalid."),
+    (
+        "AlreadyJoinedCommunityFund",
+        "The neuron has already joined the community fund.",
+    ),
+    (
+        "NotInTheCommunityFund",
+        "The neuron isn't in the community fund.",
+    ),
+];
+
+/// Describes the outcome of an update call to the governance canister: `Ok` with a
+/// summary of what was done or `Err` with an explanation of the failure. Returns
+/// `None` for replies that don't carry an outcome.
+pub fn describe_update_reply(
+    canister_id: Principal,
+    method_name: &str,
+    blob: &[u8],
+) -> Option<Result<String, String>> {
+    if canister_id != governance_canister_id() {
+        return None;
+    }
+    let neuron_id =
+        |id: Option<NeuronId>| id.map_or_else(|| "-".to_string(), |id| id.id.to_string());
+    match method_name {
+        "manage_neuron" => {
+            let outcome = match Decode!(blob, ManageNeuronResponse).ok()?.command? {
+                CommandResponse::Error(error) => return Some(Err(render_error(&error))),
+                CommandResponse::Spawn(response) => {
+                    format!("Spawned neuron {}", neuron_id(response.created_neuron_id))
+                }
+                CommandResponse::Split(response) => {
+                    format!("Split off neuron {}", neuron_id(response.created_neuron_id))
+                }
+                CommandResponse::DisburseToNeuron(response) => format!(
+                    "Disbursed to neuron {}",
+                    neuron_id(response.created_neuron_id)
+                ),
+                CommandResponse::ClaimOrRefresh(response) => format!(
+                    "Refreshed neuron {}",
+                    neuron_id(response.refreshed_neuron_id)
+                ),
+                CommandResponse::MakeProposal(response) => {
+                    format!(
+                        "Submitted proposal {}",
+                        response
+                            .proposal_id
+                            .map_or_else(|| "-".to_string(), |id| id.id.to_string())
+                    )
+                }
+                CommandResponse::MergeMaturity(response) => format!(
+                    "Merged {} ICP of maturity, the stake is now {} ICP",
+                    format_e8s(response.merged_maturity_e8s),
+                    format_e8s(response.new_stake_e8s)
+                ),
+                CommandResponse::Disburse(response) => {
+                    format!("Disbursed in block {}", response.transfer_block_height)
+                }
+                CommandResponse::StakeMaturity(response) => format!(
+                    "Staked maturity is now {} ICP, with {} ICP of maturity left",
+                    format_e8s(response.staked_maturity_e8s),
+                    format_e8s(response.maturity_e8s)
+                ),
+                CommandResponse::DisburseMaturity(response) => {
+                    match response.amount_disbursed_e8s {
+                        Some(e8s) => format!("Disbursing {} ICP of maturity", format_e8s(e8s)),
+                        None => "Disbursing maturity".to_string(),
+                    }
+                }
+                CommandResponse::RefreshVotingPower(_) => "Refreshed voting power".to_string(),
+                _ => "OK".to_string(),
+            };
+            Some(Ok(outcome))
+        }
+        "claim_or_refresh_neuron_from_account" => {
+            match Decode!(blob, ClaimOrRefreshNeuronFromAccountResponse)
+                .ok()?
+                .result?
+            {
+                ClaimOrRefreshResult::Error(error) => Some(Err(render_error(&GovernanceError {
+                    error_message: error.error_message,
+                    error_type: error.error_type,
+                }))),
+                ClaimOrRefreshResult::NeuronId(id) => {
+                    Some(Ok(format!("Refreshed neuron {}", id.id)))
+                }
+            }
+        }
+        "claim_gtc_neurons" | "transfer_gtc_neuron" => {
+            match Decode!(blob, Result<(), GovernanceError>).ok()? {
+                Ok(()) => Some(Ok("OK".to_string())),
+                Err(error) => Some(Err(render_error(&error))),
+            }
+        }
+        _ => None,
+    }
+}
+
+fn render_proposals(proposals: &[ProposalInfo]) -> String {
+    if proposals.is_empty() {
+        return "No proposals found".to_string();
+    }
+    proposals
+        .iter()
+        .map(|info| render_proposal(info, false))
+        .collect::<Vec<_>>()
+        .join("\n\n")
+}
+
+/// Returns a human-readable description of durations and dates in a `manage_neuron`
+/// argument, to be shown next to their raw values.
+pub fn describe_manage_neuron(arg: &[u8]) -> Option<String> {
+    let operation = match Decode!(arg, ManageNeuron).ok()?.command? {
+        Command::Configure(Configure { operation }) => operation?,
+        _ => return None,
+    };
+    match operation {
+        Operation::IncreaseDissolveDelay(delay) => {
+            let seconds = delay.additional_dissolve_delay_seconds as u64;
+            Some(format!(
+                "Additional dissolve delay: {} ({} seconds)",
+                format_duration(seconds),
+                seconds
+            ))
+        }
+        Operation::SetDissolveTimestamp(timestamp) => {
+            let seconds = timestamp.dissolve_timestamp_seconds;
+            Some(format!(
+                "Dissolve timestamp: {} ({} from now)",
+                format_timestamp(seconds),
+                format_duration(seconds.saturating_sub(now_seconds()))
+            ))
+        }
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use candid::Encode;
+
+    fn manage_neuron_reply(command: CommandResponse) -> Vec<u8> {
+        Encode!(&ManageNeuronResponse {
+            command: Some(command)
+        })
+        .unwrap()
+    }
+
+    #[test]
+    fn describes_manage_neuron_replies() {
+        let reply = manage_neuron_reply(CommandResponse::Split(SpawnResponse {
+            created_neuron_id: Some(NeuronId { id: 7 }),
+        }));
+        assert_eq!(
+            describe_update_reply(governance_canister_id(), "manage_neuron", &reply),
+            Some(Ok("Split off neuron 7".to_string()))
+        );
+
+        let reply = manage_neuron_reply(CommandResponse::MergeMaturity(MergeMaturityResponse {
+            merged_maturity_e8s: 50_000_000,
+            new_stake_e8s: 1_050_000_000,
+        }));
+        assert_eq!(
+            describe_update_reply(governance_canister_id(), "manage_neuron", &reply),
+            Some(Ok(
+                "Merged 0.50000000 ICP of maturity, the stake is now 10.50000000 ICP".to_string()
+            ))
+        );
+    }
+
+    #[test]
+    fn describes_governance_errors() {
+        let reply = manage_neuron_reply(CommandResponse::Error(GovernanceError {
+            error_message: "Neuron not found: 7".to_string(),
+            error_type: 4,
+        }));
+        assert_eq!(
+            describe_update_reply(governance_canister_id(), "manage_neuron", &reply),
+            Some(Err(
+                "Error: NotFound (type 4): Neuron not found: 7\nThe neuron or proposal doesn't exist."
+                    .to_string()
+            ))
+        );
+    }
+
+    #[test]
+    fn ignores_other_canisters_and_undecodable_replies() {
+        let reply = manage_neuron_reply(CommandResponse::Configure(Empty {}));
+        assert_eq!(
+            describe_update_reply(Principal::anonymous(), "manage_neuron", &reply),
+            None
+        );
+        assert_eq!(
+            describe_update_reply(
+                governance_canister_id(),
+                "manage_neuron",
+                &Encode!(&42u64).unwrap()
+            ),
+            None
+        );
+    }
+}
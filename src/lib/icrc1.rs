@@ -0,0 +1,88 @@
+//! Types of the ICRC-1 and ICRC-2 token standards, used by the SNS and ckBTC ledgers.
+use candid::{CandidType, Decode, Deserialize, Nat, Principal};
+
+/// An ICRC-1 account: an owner and an optional 32-byte subaccount.
+#[derive(CandidType)]
+pub struct Account {
+    pub owner: Principal,
+    pub subaccount: Option<Vec<u8>>,
+}
+
+/// The argument of `icrc1_transfer`.
+#[derive(CandidType)]
+pub struct TransferArg {
+    pub from_subaccount: Option<Vec<u8>>,
+    pub to: Account,
+    pub amount: Nat,
+    pub fee: Option<Nat>,
+    pub memo: Option<Vec<u8>>,
+    pub created_at_time: Option<u64>,
+}
+
+/// The argument of `icrc2_approve`.
+#[derive(CandidType)]
+pub struct ApproveArgs {
+    pub fee: Option<Nat>,
+    pub memo: Option<Vec<u8>>,
+    pub from_subaccount: Option<Vec<u8>>,
+    pub created_at_time: Option<u64>,
+    pub amount: Nat,
+    pub expected_allowance: Option<Nat>,
+    pub expires_at: Option<u64>,
+    pub spender: Account,
+}
+
+/// The errors of `icrc1_transfer` and `icrc2_approve`.
+#[derive(CandidType, Deserialize, Debug)]
+pub enum LedgerError {
+    BadFee { expected_fee: Nat },
+    BadBurn { min_burn_amount: Nat },
+    InsufficientFunds { balance: Nat },
+    AllowanceChanged { current_allowance: Nat },
+    TooOld,
+    CreatedInFuture { ledger_time: u64 },
+    Expired { ledger_time: u64 },
+    Duplicate { duplicate_of: Nat },
+    TemporarilyUnavailable,
+    GenericError { error_code: Nat, message: String },
+}
+
+fn render_error(error: &LedgerError) -> String {
+    match error {
+        LedgerError::BadFee { expected_fee } => {
+            format!("Error: BadFee, the fee must be {}", expected_fee)
+        }
+        LedgerError::BadBurn { min_burn_amount } => {
+            format!("Error: BadBurn, the minimum burn is {}", min_burn_amount)
+        }
+        LedgerError::InsufficientFunds { balance } => {
+            format!("Error: InsufficientFunds, the balance is {}", balance)
+        }
+        LedgerError::AllowanceChanged { current_allowance } => format!(
+            "Error: AllowanceChanged, the allowance is {}",
+            current_allowance
+        ),
+        LedgerError::Duplicate { duplicate_of } => format!(
+            "Error: Duplicate, the same transaction is in block {}",
+            duplicate_of
+        ),
+        LedgerError::GenericError {
+            error_code,
+            message,
+        } => format!("Error: {} (code {})", message, error_code),
+        error => format!("Error: {:?}", error),
+    }
+}
+
+/// Describes the outcome of an ICRC-1 transfer or ICRC-2 approval.
+pub fn describe_update_reply(method_name: &str, blob: &[u8]) -> Option<Result<String, String>> {
+    match method_name {
+        "icrc1_transfer" | "icrc2_approve" => {
+            match Decode!(blob, Result<Nat, LedgerError>).ok()? {
+                Ok(block_index) => Some(Ok(format!("Done in block {}", block_index))),
+                Err(error) => Some(Err(render_error(&error))),
+            }
+        }
+        _ => None,
+    }
+}
@@ -0,0 +1,92 @@
+//! The journal of the messages sent from this machine, kept so that a message isn't sent
+//! twice by mistake. It is an append-only file with one JSON entry per line.
+use crate::lib::{duration::now_seconds, AnyhowResult};
+use anyhow::{anyhow, Context};
+use serde::{Deserialize, Serialize};
+use std::collections::HashSet;
+use std::io::{BufRead, BufReader, Write};
+use std::path::PathBuf;
+
+/// An entry of the journal: a message was submitted, or its outcome became known.
+#[derive(Serialize, Deserialize)]
+pub struct Entry {
+    pub request_id: String,
+    pub canister_id: String,
+    pub method_name: String,
+    /// The time of the entry, in seconds since the UNIX epoch.
+    pub time: u64,
+    /// "sending" right before the message is sent, "submitted" once the IC accepted it, or
+    /// the outcome of the message: replied, error, rejected or unknown.
+    pub state: String,
+}
+
+pub struct Journal {
+    path: PathBuf,
+}
+
+impl Journal {
+    /// Opens the journal at the path, by default `~/.quill/journal.jsonl`.
+    pub fn open(path: Option<&str>) -> AnyhowResult<Self> {
+        let path = match path {
+            Some(path) => PathBuf::from(path),
+            None => PathBuf::from(
+                std::env::var("HOME")
+                    .map_err(|_| anyhow!("Couldn't find the home directory, use --journal"))?,
+            )
+            .join(".quill")
+            .join("journal.jsonl"),
+        };
+        Ok(Journal { path })
+    }
+
+    /// Returns the entries of the journal about the given requests, oldest first.
+    pub fn find(&self, request_ids: &HashSet<String>) -> AnyhowResult<Vec<Entry>> {
+        let file = match std::fs::File::open(&self.path) {
+            Ok(file) => file,
+            Err(err) if err.kind() == std::io::ErrorKind::NotFound => return Ok(Vec::new()),
+            Err(err) => {
+                return Err(err).with_context(|| format!("Couldn't read {}", self.path.display()))
+            }
+        };
+        let mut entries = Vec::new();
+        for line in BufReader::new(file).lines() {
+            let line = line?;
+            if line.trim().is_empty() {
+                continue;
+            }
+            let entry: Entry = serde_json::from_str(&line)
+                .with_context(|| format!("Invalid entry in {}", self.path.display()))?;
+            if request_ids.contains(&entry.request_id) {
+                entries.push(entry);
+            }
+        }
+        Ok(entries)
+    }
+
+    /// Appends an entry about the request, dated now.
+    pub fn append(
+        &self,
+        request_id: &str,
+        canister_id: &str,
+        method_name: &str,
+        state: &str,
+    ) -> AnyhowResult {
+        let entry = Entry {
+            request_id: request_id.to_string(),
+            canister_id: canister_id.to_string(),
+            method_name: method_name.to_string(),
+            time: now_seconds(),
+            state: state.to_string(),
+        };
+        if let Some(dir) = self.path.parent() {
+            std::fs::create_dir_all(dir)?;
+        }
+        let mut file = std::fs::OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(&self.path)
+            .with_context(|| format!("Couldn't write to {}", self.path.display()))?;
+        writeln!(file, "{}", serde_json::to_string(&entry)?)?;
+        Ok(())
+    }
+}
@@ -31,8 +31,18 @@ pub fn get_ic_url() -> String {
     std::env::var("IC_URL").unwrap_or_else(|_| IC_URL.to_string())
 }
 
+pub mod candid_json;
+pub mod ckbtc;
+pub mod duration;
+pub mod envelope;
+pub mod governance;
+pub mod icrc1;
+pub mod journal;
+pub mod neuron_report;
 pub mod request_status;
 pub mod signing;
+pub mod sns;
+pub mod validation;
 
 pub type AnyhowResult<T = ()> = anyhow::Result<T>;
 
@@ -52,11 +62,37 @@ pub fn get_local_candid(canister_id: Principal) -> AnyhowResult<String> {
     } else if canister_id == ledger_canister_id() {
         String::from_utf8(include_bytes!("../../candid/ledger.did").to_vec())
             .map_err(|e| anyhow!(e))
+    } else if canister_id == ckbtc::minter_canister_id() {
+        Ok(include_str!("../../candid/ckbtc_minter.did").to_string())
+    } else if canister_id == ckbtc::ledger_canister_id() {
+        Ok(include_str!("../../candid/icrc1.did").to_string())
     } else {
         Ok(Default::default())
     }
 }
 
+/// Candid of canisters deployed under many ids, such as the canisters of an SNS. Messages
+/// to canisters without a local candid are decoded with the first of these declaring the
+/// method.
+const GENERIC_CANDID: &[&str] = &[
+    include_str!("../../candid/sns_governance.did"),
+    include_str!("../../candid/sns_swap.did"),
+    include_str!("../../candid/icrc1.did"),
+    include_str!("../../candid/ckbtc_minter.did"),
+];
+
+/// Returns the candid type of a method of the canister, from its local candid or from the
+/// generic candid declaring the method.
+pub fn get_method_type(canister_id: Principal, method_name: &str) -> Option<(TypeEnv, Function)> {
+    let spec = get_local_candid(canister_id).unwrap_or_default();
+    if !spec.is_empty() {
+        return get_candid_type(spec, method_name);
+    }
+    GENERIC_CANDID
+        .iter()
+        .find_map(|spec| get_candid_type(spec.to_string(), method_name))
+}
+
 /// Returns pretty-printed encoding of a candid value.
 pub fn get_idl_string(
     blob: &[u8],
@@ -64,21 +100,42 @@ pub fn get_idl_string(
     method_name: &str,
     part: &str,
 ) -> Result<String, String> {
-    let spec = get_local_candid(canister_id).unwrap_or_default();
-    let method_type = get_candid_type(spec, method_name);
+    get_idl_args(blob, canister_id, method_name, part).map(|args| args.to_string())
+}
+
+/// Decodes a candid value, with the types of the method if its candid is known. A value
+/// that doesn't match the local candid of the canister is an error. The generic candid is
+/// matched by method name only, so a value that doesn't match it is decoded without types,
+/// with a warning.
+pub fn get_idl_args(
+    blob: &[u8],
+    canister_id: Principal,
+    method_name: &str,
+    part: &str,
+) -> Result<candid::IDLArgs, String> {
+    let method_type = get_method_type(canister_id, method_name);
     match method_type {
         None => candid::IDLArgs::from_bytes(blob),
-        Some((env, func)) => candid::IDLArgs::from_bytes_with_types(
-            blob,
-            &env,
-            if part == "args" {
+        Some((env, func)) => {
+            let types = if part == "args" {
                 &func.args
             } else {
                 &func.rets
-            },
-        ),
+            };
+            match candid::IDLArgs::from_bytes_with_types(blob, &env, types) {
+                Err(err) if get_local_candid(canister_id).unwrap_or_default().is_empty() => {
+                    eprintln!(
+                        "Warning: the {} of {} doesn't match the candid of the method and is shown without types: {}",
+                        if part == "args" { "argument" } else { "reply" },
+                        method_name,
+                        err
+                    );
+                    candid::IDLArgs::from_bytes(blob)
+                }
+                result => result,
+            }
+        }
     }
-    .map(|args| args.to_string())
     .map_err(|err| err.to_string())
 }
 
@@ -143,6 +200,18 @@ pub fn get_identity(pem: Option<String>) -> Box<dyn Identity + Sync + Send> {
     }
 }
 
+/// Returns the DER-encoded public key of the identity in the PEM, which must be a
+/// secp256k1 key.
+pub fn get_secp256k1_public_key(pem: &str) -> AnyhowResult<Vec<u8>> {
+    let identity = Secp256k1Identity::from_pem(pem.as_bytes())
+        .map_err(|_| anyhow!("The identity must be a secp256k1 key"))?;
+    identity
+        .sign(&[])
+        .map_err(|err| anyhow!(err))?
+        .public_key
+        .ok_or_else(|| anyhow!("The identity has no public key"))
+}
+
 pub fn parse_query_response(response: Vec<u8>) -> AnyhowResult<Vec<u8>> {
     let cbor: Value = serde_cbor::from_slice(&response)
         .map_err(|_| anyhow!("Invalid cbor data in the content of the message."))?;
@@ -153,11 +222,10 @@ pub fn parse_query_response(response: Vec<u8>) -> AnyhowResult<Vec<u8>> {
             m.get(&Value::Text("reject_code".to_string())),
             m.get(&Value::Text("reject_message".to_string())),
         ) {
-            return Ok(
-                format!("Rejected (code {}): {}", reject_code, reject_message)
-                    .as_bytes()
-                    .to_vec(),
-            );
+            return Err(anyhow!(ic_agent::AgentError::ReplicaError {
+                reject_code: *reject_code as u64,
+                reject_message: reject_message.clone(),
+            }));
         }
 
         // Try to decode a successful response.
@@ -248,10 +316,15 @@ pub async fn send_ingress(message: &Ingress) -> AnyhowResult<IngressResult> {
     }
 }
 
+/// Returns whether the method is a query of a canister we have the candid of. Methods
+/// only known from the generic candid are update calls: the canister may declare them
+/// otherwise, and `raw` sends queries only when asked with `--query`.
 pub fn is_query(canister_id: Principal, method_name: &str) -> bool {
     let spec = get_local_candid(canister_id).unwrap_or_default();
-    let method_type = get_candid_type(spec, method_name);
-    match &method_type {
+    if spec.is_empty() {
+        return false;
+    }
+    match &get_candid_type(spec, method_name) {
         Some((_, f)) => f.is_query(),
         _ => false,
     }
@@ -0,0 +1,391 @@
+//! Renders the response of `list_neurons` as a report with one row per neuron.
+use crate::lib::{
+    duration::{format_duration, now_seconds},
+    governance::{
+        describe_dissolve_state, format_e8s, neuron_state_name, topic_name, vote_name,
+        ListNeuronsResponse,
+    },
+    AnyhowResult,
+};
+use anyhow::anyhow;
+use candid::Decode;
+use serde::Serialize;
+use std::str::FromStr;
+
+/// The number of recent ballots shown for every neuron.
+const RECENT_BALLOTS: usize = 5;
+
+#[derive(Clone, Copy, PartialEq)]
+pub enum ReportFormat {
+    Table,
+    Csv,
+    Json,
+}
+
+impl FromStr for ReportFormat {
+    type Err = String;
+
+    fn from_str(format: &str) -> Result<Self, Self::Err> {
+        match format {
+            "table" => Ok(ReportFormat::Table),
+            "csv" => Ok(ReportFormat::Csv),
+            "json" => Ok(ReportFormat::Json),
+            _ => Err("The report format must be one of table, csv or json".to_string()),
+        }
+    }
+}
+
+#[derive(Serialize)]
+struct Row {
+    id: String,
+    stake_icp: String,
+    maturity_icp: String,
+    dissolve_state: String,
+    age: String,
+    voting_power_icp: String,
+    hot_keys: Vec<String>,
+    followees: Vec<String>,
+    recent_ballots: Vec<String>,
+}
+
+#[derive(Serialize)]
+struct Totals {
+    neurons: usize,
+    stake_icp: String,
+    maturity_icp: String,
+    voting_power_icp: String,
+}
+
+#[derive(Serialize)]
+struct Report {
+    neurons: Vec<Row>,
+    totals: Totals,
+}
+
+const HEADERS: [&str; 9] = [
+    "Neuron id",
+    "Stake (ICP)",
+    "Maturity (ICP)",
+    "Dissolve state",
+    "Age",
+    "Voting power",
+    "Hot keys",
+    "Followees",
+    "Recent ballots",
+];
+
+/// Decodes a `list_neurons` reply and renders it in the given format.
+pub fn render(blob: &[u8], format: ReportFormat) -> AnyhowResult<String> {
+    let response = Decode!(blob, ListNeuronsResponse)
+        .map_err(|err| anyhow!("Couldn't decode the list of neurons: {}", err))?;
+    let report = build(response);
+    Ok(match format {
+        ReportFormat::Json => serde_json::to_string_pretty(&report)?,
+        ReportFormat::Csv => render_csv(&report),
+        ReportFormat::Table => render_table(&report),
+    })
+}
+
+fn build(response: ListNeuronsResponse) -> Report {
+    let now = now_seconds();
+    let mut totals = (0, 0, 0);
+    let mut rows = Vec::new();
+    let full_ids: Vec<u64> = response
+        .full_neurons
+        .iter()
+        .filter_map(|neuron| neuron.id.as_ref().map(|id| id.id))
+        .collect();
+    for neuron in response.full_neurons {
+        let id = neuron.id.as_ref().map(|id| id.id);
+        let info = response
+            .neuron_infos
+            .iter()
+            .find(|(neuron_id, _)| Some(*neuron_id) == id)
+            .map(|(_, info)| info);
+        let stake = neuron
+            .cached_neuron_stake_e8s
+            .saturating_sub(neuron.neuron_fees_e8s);
+        let voting_power = info.map_or(0, |info| info.voting_power);
+        let age = match info {
+            Some(info) => info.age_seconds,
+            None if neuron.aging_since_timestamp_seconds < now => {
+                now - neuron.aging_since_timestamp_seconds
+            }
+            None => 0,
+        };
+        totals.0 += stake;
+        totals.1 += neuron.maturity_e8s_equivalent;
+        totals.2 += voting_power;
+
+        let mut followees = neuron.followees.clone();
+        followees.sort_by_key(|(topic, _)| *topic);
+        rows.push(Row {
+            id: id.map_or_else(|| "-".to_string(), |id| id.to_string()),
+            stake_icp: format_e8s(stake),
+            maturity_icp: format_e8s(neuron.maturity_e8s_equivalent),
+            dissolve_state: describe_dissolve_state(&neuron.dissolve_state),
+            age: format_duration(age),
+            voting_power_icp: format_e8s(voting_power),
+            hot_keys: neuron.hot_keys.iter().map(|key| key.to_string()).collect(),
+            followees: followees
+                .iter()
+                .map(|(topic, followees)| {
+                    format!(
+                        "{}: {}",
+                        topic_name(*topic),
+                        followees
+                            .followees
+                            .iter()
+                            .map(|id| id.id.to_string())
+                            .collect::<Vec<_>>()
+                            .join(" ")
+                    )
+                })
+                .collect(),
+            recent_ballots: neuron
+                .recent_ballots
+                .iter()
+                .take(RECENT_BALLOTS)
+                .map(|ballot| {
+                    format!(
+                        "{}: {}",
+                        ballot
+                            .proposal_id
+                            .as_ref()
+                            .map_or_else(|| "-".to_string(), |id| id.id.to_string()),
+                        vote_name(ballot.vote)
+                    )
+                })
+                .collect(),
+        });
+    }
+
+    // Neurons that only have public information, e.g. those the caller follows, are
+    // listed with what is known about them.
+    let mut partial = Vec::new();
+    for (id, info) in &response.neuron_infos {
+        if full_ids.contains(id) {
+            continue;
+        }
+        partial.push(id.to_string());
+        totals.2 += info.voting_power;
+        rows.push(Row {
+            id: id.to_string(),
+            stake_icp: "-".to_string(),
+            maturity_icp: "-".to_string(),
+            dissolve_state: format!(
+                "{}, dissolve delay {}",
+                neuron_state_name(info.state),
+                format_duration(info.dissolve_delay_seconds)
+            ),
+            age: format_duration(info.age_seconds),
+            voting_power_icp: format_e8s(info.voting_power),
+            hot_keys: Vec::new(),
+            followees: Vec::new(),
+            recent_ballots: Vec::new(),
+        });
+    }
+    if !partial.is_empty() {
+        eprintln!(
+            "Warning: only partial data is available for neurons {}, their stake and maturity aren't included in the totals",
+            partial.join(", ")
+        );
+    }
+
+    Report {
+        totals: Totals {
+            neurons: rows.len(),
+            stake_icp: format_e8s(totals.0),
+            maturity_icp: format_e8s(totals.1),
+            voting_power_icp: format_e8s(totals.2),
+        },
+        neurons: rows,
+    }
+}
+
+fn cells(row: &Row) -> Vec<String> {
+    vec![
+        row.id.clone(),
+        row.stake_icp.clone(),
+        row.maturity_icp.clone(),
+        row.dissolve_state.clone(),
+        row.age.clone(),
+        row.voting_power_icp.clone(),
+        row.hot_keys.join(", "),
+        row.followees.join("; "),
+        row.recent_ballots.join(", "),
+    ]
+}
+
+fn totals_cells(totals: &Totals) -> Vec<String> {
+    vec![
+        format!("Total ({} neurons)", totals.neurons),
+        totals.stake_icp.clone(),
+        totals.maturity_icp.clone(),
+        String::new(),
+        String::new(),
+        totals.voting_power_icp.clone(),
+        String::new(),
+        String::new(),
+        String::new(),
+    ]
+}
+
+fn render_table(report: &Report) -> String {
+    let mut lines: Vec<Vec<String>> = vec![HEADERS.iter().map(|h| h.to_string()).collect()];
+    lines.extend(report.neurons.iter().map(cells));
+    lines.push(totals_cells(&report.totals));
+    let widths: Vec<usize> = (0..HEADERS.len())
+        .map(|column| {
+            lines
+                .iter()
+                .map(|line| line[column].chars().count())
+                .max()
+                .unwrap_or_default()
+        })
+        .collect();
+    lines
+        .iter()
+        .map(|line| {
+            line.iter()
+                .zip(widths.iter())
+                .map(|(cell, width)| format!("{:width$}", cell, width = width))
+                .collect::<Vec<_>>()
+                .join("  ")
+                .trim_end()
+                .to_string()
+        })
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+fn render_csv(report: &Report) -> String {
+    let escape = |cell: &String| {
+        if cell.contains(&[',', '"', '\n'][..]) {
+            format!("\"{}\"", cell.replace('"', "\"\""))
+        } else {
+            cell.clone()
+        }
+    };
+    let mut lines: Vec<Vec<String>> = vec![HEADERS.iter().map(|h| h.to_string()).collect()];
+    lines.extend(report.neurons.iter().map(cells));
+    lines.push(totals_cells(&report.totals));
+    lines
+        .iter()
+        .map(|line| line.iter().map(escape).collect::<Vec<_>>().join(","))
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::lib::governance::{BallotInfo, DissolveState, Followees, Neuron, NeuronInfo};
+    use candid::{Encode, Principal};
+    use ic_nns_common::pb::v1::{NeuronId, ProposalId};
+
+    fn info(
+        state: i32,
+        dissolve_delay_seconds: u64,
+        age_seconds: u64,
+        voting_power: u64,
+    ) -> NeuronInfo {
+        NeuronInfo {
+            dissolve_delay_seconds,
+            recent_ballots: Vec::new(),
+            created_timestamp_seconds: 0,
+            state,
+            retrieved_at_timestamp_seconds: 0,
+            voting_power,
+            age_seconds,
+            visibility: None,
+        }
+    }
+
+    /// A reply listing neuron 1 in full and neuron 2 with its public information only.
+    fn reply() -> Vec<u8> {
+        let neuron = Neuron {
+            id: Some(NeuronId { id: 1 }),
+            controller: None,
+            recent_ballots: vec![BallotInfo {
+                vote: 1,
+                proposal_id: Some(ProposalId { id: 42 }),
+            }],
+            maturity_e8s_equivalent: 50_000_000,
+            cached_neuron_stake_e8s: 1_000_000_000,
+            neuron_fees_e8s: 0,
+            created_timestamp_seconds: 0,
+            aging_since_timestamp_seconds: 0,
+            hot_keys: vec![Principal::anonymous()],
+            dissolve_state: Some(DissolveState::DissolveDelaySeconds(31_557_600)),
+            followees: vec![(
+                1,
+                Followees {
+                    followees: vec![NeuronId { id: 3 }],
+                },
+            )],
+            staked_maturity_e8s_equivalent: None,
+            auto_stake_maturity: None,
+            visibility: None,
+            voting_power_refreshed_timestamp_seconds: None,
+        };
+        Encode!(&ListNeuronsResponse {
+            neuron_infos: vec![
+                (1, info(1, 31_557_600, 86_400, 1_250_000_000)),
+                (2, info(2, 30 * 86_400, 0, 100_000_000)),
+            ],
+            full_neurons: vec![neuron],
+            total_pages_available: None,
+        })
+        .unwrap()
+    }
+
+    #[test]
+    fn renders_table_with_totals() {
+        let table = render(&reply(), ReportFormat::Table).unwrap();
+        let lines: Vec<&str> = table.lines().collect();
+        assert_eq!(lines.len(), 4);
+        assert!(lines[0].starts_with("Neuron id          Stake (ICP)  Maturity (ICP)"));
+        assert_eq!(
+            lines[1],
+            "1                  10.00000000  0.50000000      Locked, dissolve delay 1 year       1 day      12.50000000   2vxsx-fae  NeuronManagement: 3  42: Yes"
+        );
+        assert_eq!(
+            lines[2],
+            "2                  -            -               Dissolving, dissolve delay 30 days  0 seconds  1.00000000"
+        );
+        assert_eq!(
+            lines[3],
+            "Total (2 neurons)  10.00000000  0.50000000                                                     13.50000000"
+        );
+    }
+
+    #[test]
+    fn renders_csv_with_totals() {
+        assert_eq!(
+            render(&reply(), ReportFormat::Csv).unwrap(),
+            "Neuron id,Stake (ICP),Maturity (ICP),Dissolve state,Age,Voting power,Hot keys,Followees,Recent ballots\n\
+             1,10.00000000,0.50000000,\"Locked, dissolve delay 1 year\",1 day,12.50000000,2vxsx-fae,NeuronManagement: 3,42: Yes\n\
+             2,-,-,\"Dissolving, dissolve delay 30 days\",0 seconds,1.00000000,,,\n\
+             Total (2 neurons),10.00000000,0.50000000,,,13.50000000,,,"
+        );
+    }
+
+    #[test]
+    fn renders_json_with_totals() {
+        let report: serde_json::Value =
+            serde_json::from_str(&render(&reply(), ReportFormat::Json).unwrap()).unwrap();
+        assert_eq!(report["neurons"][0]["id"], "1");
+        assert_eq!(report["neurons"][0]["followees"][0], "NeuronManagement: 3");
+        assert_eq!(report["neurons"][1]["id"], "2");
+        assert_eq!(report["neurons"][1]["stake_icp"], "-");
+        assert_eq!(report["totals"]["neurons"], 2);
+        assert_eq!(report["totals"]["stake_icp"], "10.00000000");
+        assert_eq!(report["totals"]["voting_power_icp"], "13.50000000");
+    }
+
+    #[test]
+    fn rejects_other_replies() {
+        assert!(render(&Encode!(&42u64).unwrap(), ReportFormat::Table).is_err());
+    }
+}
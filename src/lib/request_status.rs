@@ -1,27 +1,85 @@
 use crate::lib::get_ic_url;
-use crate::lib::{get_agent, signing::RequestStatus, AnyhowResult};
+use crate::lib::{
+    duration::{format_duration, now_seconds},
+    envelope::Envelope,
+    get_agent,
+    signing::RequestStatus,
+    AnyhowResult,
+};
 use anyhow::{anyhow, Context};
 use ic_agent::agent::{Replied, RequestStatusResponse};
-use ic_agent::{AgentError, RequestId};
+use ic_agent::{Agent, AgentError, RequestId};
 use ic_types::Principal;
 use std::str::FromStr;
 use std::sync::Arc;
+use std::time::{Duration, Instant};
 
-pub async fn submit(req: &RequestStatus, silent: bool) -> AnyhowResult<Vec<u8>> {
-    let canister_id = Principal::from_text(&req.canister_id).expect("Couldn't parse canister id");
-    let request_id =
-        RequestId::from_str(&req.request_id).context("Invalid argument: request_id")?;
-    let mut agent = get_agent(None).await?;
-    agent.set_transport(ProxySignReplicaV2Transport {
-        req: req.clone(),
-        http_transport: Arc::new(
-            ic_agent::agent::http_transport::ReqwestHttpReplicaV2Transport::create(get_ic_url())
-                .unwrap(),
+/// The first delay between two polls of the request status.
+const INITIAL_POLL_DELAY: Duration = Duration::from_millis(500);
+
+/// The longest delay between two polls of the request status.
+const MAX_POLL_DELAY: Duration = Duration::from_secs(10);
+
+/// The outcome of a message couldn't be learned: the polling timed out, the request
+/// status queries expired or the IC no longer has the reply. The message may still have
+/// been executed, so `quill` exits with a distinct status.
+#[derive(Debug)]
+pub struct OutcomeUnknown(pub String);
+
+impl std::fmt::Display for OutcomeUnknown {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(&self.0)
+    }
+}
+
+impl std::error::Error for OutcomeUnknown {}
+
+/// The exit status of `quill` when the outcome of a message is unknown.
+pub const OUTCOME_UNKNOWN_EXIT_CODE: i32 = 2;
+
+/// Returns the latest expiry of the request status queries, in seconds since the UNIX epoch.
+fn last_expiry(reqs: &[RequestStatus]) -> u64 {
+    reqs.iter()
+        .filter_map(|req| Envelope::decode(&req.content).ok()?.ingress_expiry())
+        .max()
+        .unwrap_or_default()
+        / 1_000_000_000
+}
+
+/// Polls the status of the request until it is replied or rejected, with the first of the
+/// signed request status queries that hasn't expired, so that the status can be polled
+/// for as long as the last query is valid. The delay between polls doubles up to 10
+/// seconds. Gives up with `OutcomeUnknown` after `timeout`, once the last query has expired,
+/// or if the IC has dropped the reply.
+pub async fn submit(
+    reqs: &[RequestStatus],
+    silent: bool,
+    timeout: Option<Duration>,
+) -> AnyhowResult<Vec<u8>> {
+    let (agent, request_id, canister_id) = status_agent(reqs).await?;
+    let req = &reqs[0];
+    let started = Instant::now();
+    let expires_in = Duration::from_secs(last_expiry(reqs).saturating_sub(now_seconds()));
+    let (deadline, limit) = match timeout {
+        Some(timeout) if timeout < expires_in => (started + timeout, "The request timed out"),
+        _ => (
+            started + expires_in,
+            "All request status queries have expired",
         ),
-    });
+    };
+    let mut delay = INITIAL_POLL_DELAY;
+    let mut last_status = "";
     let Replied::CallReplied(blob) = async {
         loop {
-            match agent.request_status_raw(&request_id, canister_id).await? {
+            if Instant::now() >= deadline {
+                return Err(anyhow!(OutcomeUnknown(format!(
+                    "{} after {}, the outcome of request {} is unknown: it may still be executed",
+                    limit,
+                    format_duration(started.elapsed().as_secs()),
+                    req.request_id
+                ))));
+            }
+            let status = match agent.request_status_raw(&request_id, canister_id).await? {
                 RequestStatusResponse::Replied { reply } => return Ok(reply),
                 RequestStatusResponse::Rejected {
                     reject_code,
@@ -32,29 +90,64 @@ pub async fn submit(req: &RequestStatus, silent: bool) -> AnyhowResult<Vec<u8>>
                         reject_message,
                     }))
                 }
-                RequestStatusResponse::Unknown
-                | RequestStatusResponse::Received
-                | RequestStatusResponse::Processing => {
-                    if !silent {
-                        println!("The request is being processed...");
-                    }
-                }
+                RequestStatusResponse::Unknown => "unknown to the IC yet",
+                RequestStatusResponse::Received => "received",
+                RequestStatusResponse::Processing => "being processed",
                 RequestStatusResponse::Done => {
-                    return Err(anyhow!(AgentError::RequestStatusDoneNoReply(String::from(
-                        request_id
-                    ),)))
+                    return Err(anyhow!(OutcomeUnknown(format!(
+                        "The request {} was executed but the IC no longer has its reply",
+                        req.request_id
+                    ))))
                 }
             };
+            if !silent && status != last_status {
+                eprintln!("The request is {}...", status);
+            }
+            last_status = status;
 
-            std::thread::sleep(std::time::Duration::from_millis(500));
+            let remaining = deadline.saturating_duration_since(Instant::now());
+            tokio::time::sleep(delay.min(remaining)).await;
+            delay = (delay * 2).min(MAX_POLL_DELAY);
         }
     }
     .await?;
     Ok(blob)
 }
 
+/// Reads the status of the request once, or returns `None` if all request status queries
+/// have expired.
+pub async fn read(reqs: &[RequestStatus]) -> AnyhowResult<Option<RequestStatusResponse>> {
+    if last_expiry(reqs) <= now_seconds() {
+        return Ok(None);
+    }
+    let (agent, request_id, canister_id) = status_agent(reqs).await?;
+    Ok(Some(
+        agent.request_status_raw(&request_id, canister_id).await?,
+    ))
+}
+
+/// Returns an agent reading the status of the request with the signed request status
+/// queries, the id of the request and the canister it was sent to.
+async fn status_agent(reqs: &[RequestStatus]) -> AnyhowResult<(Agent, RequestId, Principal)> {
+    let req = reqs
+        .first()
+        .ok_or_else(|| anyhow!("No request status query"))?;
+    let canister_id = Principal::from_text(&req.canister_id).expect("Couldn't parse canister id");
+    let request_id =
+        RequestId::from_str(&req.request_id).context("Invalid argument: request_id")?;
+    let mut agent = get_agent(None).await?;
+    agent.set_transport(ProxySignReplicaV2Transport {
+        reqs: reqs.to_vec(),
+        http_transport: Arc::new(
+            ic_agent::agent::http_transport::ReqwestHttpReplicaV2Transport::create(get_ic_url())
+                .unwrap(),
+        ),
+    });
+    Ok((agent, request_id, canister_id))
+}
+
 pub(crate) struct ProxySignReplicaV2Transport {
-    req: RequestStatus,
+    reqs: Vec<RequestStatus>,
     http_transport: Arc<dyn 'static + ReplicaV2Transport + Send + Sync>,
 }
 
@@ -68,10 +161,25 @@ impl ReplicaV2Transport for ProxySignReplicaV2Transport {
         _canister_id: Principal,
         _content: Vec<u8>,
     ) -> Pin<Box<dyn Future<Output = Result<Vec<u8>, AgentError>> + Send + 'a>> {
-        self.http_transport.read_state(
-            Principal::from_text(self.req.canister_id.clone()).unwrap(),
-            hex::decode(self.req.content.clone()).unwrap(),
-        )
+        let now = now_seconds() * 1_000_000_000;
+        let req = self.reqs.iter().find(|req| {
+            Envelope::decode(&req.content)
+                .ok()
+                .and_then(|envelope| envelope.ingress_expiry())
+                .map_or(false, |expiry| expiry > now)
+        });
+        match req {
+            Some(req) => self.http_transport.read_state(
+                Principal::from_text(req.canister_id.clone()).unwrap(),
+                hex::decode(req.content.clone()).unwrap(),
+            ),
+            None => Box::pin(async {
+                Err(AgentError::MessageError(
+                    "All request status queries have expired, the outcome of the message is unknown"
+                        .to_string(),
+                ))
+            }),
+        }
     }
 
     fn call<'a>(
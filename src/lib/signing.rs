@@ -1,15 +1,73 @@
 use crate::lib::get_idl_string;
-use crate::lib::AnyhowResult;
+use crate::lib::{duration::now_seconds, envelope, AnyhowResult};
 use anyhow::anyhow;
 use ic_agent::agent::QueryBuilder;
 use ic_agent::agent::UpdateBuilder;
-use ic_agent::Agent;
 use ic_agent::RequestId;
+use ic_agent::{Agent, Identity};
 use ic_types::principal::Principal;
 use serde::{Deserialize, Serialize};
 use serde_cbor::Value;
+use std::cell::RefCell;
 use std::convert::TryFrom;
-use std::time::Duration;
+use std::time::{Duration, UNIX_EPOCH};
+
+/// The longest time a message can be valid for, as accepted by the IC.
+pub const MAX_INGRESS_EXPIRY_SECONDS: u64 = 5 * 60;
+
+/// The clock drift the IC tolerates when checking the expiry of a message.
+pub const PERMITTED_DRIFT_SECONDS: u64 = 60;
+
+/// How messages are signed, set from the command line before anything is signed.
+pub struct SigningConfig {
+    /// The identity signing the request status queries.
+    pub identity: Box<dyn Identity + Sync + Send>,
+    /// How long every signed copy of a message is valid, in seconds.
+    pub ingress_expiry_seconds: u64,
+    /// The number of consecutive validity windows a copy of every message is signed for.
+    pub windows: u32,
+    /// The number of request status queries signed for every message, each expiring
+    /// 5 minutes after the previous one.
+    pub status_queries: u32,
+}
+
+thread_local! {
+    static CONFIG: RefCell<Option<SigningConfig>> = RefCell::new(None);
+}
+
+/// Sets how the following messages are signed.
+pub fn configure(config: SigningConfig) {
+    CONFIG.with(|current| *current.borrow_mut() = Some(config));
+}
+
+/// Returns the expiry, in nanoseconds since the UNIX epoch, of the copy of a message
+/// signed for the given validity window.
+fn window_expiry(window: u32) -> u64 {
+    let expiry = CONFIG.with(|config| {
+        config
+            .borrow()
+            .as_ref()
+            .map_or(MAX_INGRESS_EXPIRY_SECONDS, |config| {
+                config.ingress_expiry_seconds
+            })
+    });
+    (now_seconds() + expiry * (window as u64 + 1)) * 1_000_000_000
+}
+
+/// Returns the number of validity windows copies of every message are signed for.
+fn windows() -> u32 {
+    CONFIG.with(|config| config.borrow().as_ref().map_or(1, |config| config.windows))
+}
+
+/// Returns the number of request status queries signed for every message.
+fn status_queries() -> u32 {
+    CONFIG.with(|config| {
+        config
+            .borrow()
+            .as_ref()
+            .map_or(1, |config| config.status_queries)
+    })
+}
 
 #[derive(Debug)]
 pub struct MessageError(String);
@@ -40,16 +98,42 @@ pub struct Ingress {
     pub call_type: String,
     pub request_id: Option<String>,
     pub content: String,
+    /// Copies of the message signed for the following validity windows.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub later_copies: Vec<Ingress>,
 }
 
 #[derive(Debug, Default, Clone, Deserialize, Serialize)]
 pub struct IngressWithRequestId {
     pub ingress: Ingress,
     pub request_status: RequestStatus,
+    /// Further request status queries, each expiring after the previous one.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub later_status_queries: Vec<RequestStatus>,
+    /// Copies of the message signed for the following validity windows.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub later_copies: Vec<IngressWithRequestId>,
+}
+
+impl IngressWithRequestId {
+    /// Returns the request status queries of the message, in the order they expire.
+    pub fn status_queries(&self) -> Vec<RequestStatus> {
+        std::iter::once(&self.request_status)
+            .chain(&self.later_status_queries)
+            .cloned()
+            .collect()
+    }
 }
 
 impl Ingress {
     pub fn parse(&self) -> AnyhowResult<(Principal, Principal, String, Result<String, String>)> {
+        let (sender, canister_id, method_name, arg) = self.parse_raw()?;
+        let args = get_idl_string(&arg, canister_id, &method_name, "args");
+        Ok((sender, canister_id, method_name, args))
+    }
+
+    /// Returns the sender, the canister id, the method name and the undecoded argument.
+    pub fn parse_raw(&self) -> AnyhowResult<(Principal, Principal, String, Vec<u8>)> {
         let cbor: Value = serde_cbor::from_slice(&hex::decode(&self.content)?)
             .map_err(|_| anyhow!("Invalid cbor data in the content of the message."))?;
         if let Value::Map(m) = cbor {
@@ -70,12 +154,7 @@ impl Ingress {
                 ) {
                     let sender = Principal::try_from(sender)?;
                     let canister_id = Principal::try_from(canister_id)?;
-                    return Ok((
-                        sender,
-                        canister_id,
-                        method_name.to_string(),
-                        get_idl_string(arg, canister_id, method_name, "args"),
-                    ));
+                    return Ok((sender, canister_id, method_name.to_string(), arg.clone()));
                 }
             }
         }
@@ -83,39 +162,51 @@ impl Ingress {
     }
 }
 
+/// Signs the query for the status of the request, valid until the given expiry in
+/// nanoseconds since the UNIX epoch.
 pub fn request_status_sign(
     agent: Agent,
     request_id: RequestId,
     canister_id: Principal,
+    expiry: u64,
 ) -> AnyhowResult<RequestStatus> {
-    let val = agent.sign_request_status(canister_id, request_id)?;
+    let content = CONFIG.with(|config| match config.borrow().as_ref() {
+        Some(config) => {
+            envelope::sign_request_status(config.identity.as_ref(), request_id.as_slice(), expiry)
+        }
+        None => Ok(agent
+            .sign_request_status(canister_id, request_id)?
+            .signed_request_status),
+    })?;
     Ok(RequestStatus {
         canister_id: canister_id.to_string(),
         request_id: request_id.into(),
-        content: hex::encode(val.signed_request_status),
+        content: hex::encode(content),
     })
 }
 
+/// Signs a message valid until the given expiry in nanoseconds since the UNIX epoch.
 pub fn sign(
     agent: Agent,
     canister_id: Principal,
     method_name: &str,
     is_query: bool,
     args: Vec<u8>,
+    expiry: u64,
 ) -> AnyhowResult<SignedMessageWithRequestId> {
-    let ingress_expiry = Duration::from_secs(5 * 60);
+    let ingress_expiry = UNIX_EPOCH + Duration::from_nanos(expiry);
 
     let (content, request_id) = if is_query {
         let bytes = QueryBuilder::new(&agent, canister_id, method_name.to_string())
             .with_arg(args)
-            .expire_after(ingress_expiry)
+            .expire_at(ingress_expiry)
             .sign()?
             .signed_query;
         (hex::encode(bytes), None)
     } else {
         let signed_update = UpdateBuilder::new(&agent, canister_id, method_name.to_string())
             .with_arg(args)
-            .expire_after(ingress_expiry)
+            .expire_at(ingress_expiry)
             .sign()?;
 
         (
@@ -129,12 +220,14 @@ pub fn sign(
             call_type: if is_query { "query" } else { "update" }.to_string(),
             request_id: request_id.map(|v| v.into()),
             content,
+            later_copies: Vec::new(),
         },
         request_id,
     })
 }
 
-/// Generates a bundle of signed messages (ingress + request status query).
+/// Generates a bundle of signed messages (ingress + request status query), with copies
+/// for the following validity windows.
 pub fn sign_ingress_with_request_status_query(
     agent: Agent,
     canister_id: Principal,
@@ -142,19 +235,46 @@ pub fn sign_ingress_with_request_status_query(
     args: Vec<u8>,
 ) -> AnyhowResult<IngressWithRequestId> {
     let is_query = crate::lib::is_query(canister_id, method_name);
-    let msg_with_req_id = sign(agent.clone(), canister_id, method_name, is_query, args)?;
-    let request_id = msg_with_req_id
-        .request_id
-        .expect("No request id for transfer call found");
-    let request_status = request_status_sign(agent, request_id, canister_id)?;
-    let message = IngressWithRequestId {
-        ingress: msg_with_req_id.message,
-        request_status,
-    };
+    let mut copies = Vec::new();
+    for window in 0..windows() {
+        let expiry = window_expiry(window);
+        let msg_with_req_id = sign(
+            agent.clone(),
+            canister_id,
+            method_name,
+            is_query,
+            args.clone(),
+            expiry,
+        )?;
+        let request_id = msg_with_req_id.request_id.ok_or_else(|| {
+            anyhow!(
+                "{} is a query method, it can't be signed with a request status query",
+                method_name
+            )
+        })?;
+        let request_status = request_status_sign(agent.clone(), request_id, canister_id, expiry)?;
+        let mut later_status_queries = Vec::new();
+        for query in 1..status_queries() {
+            later_status_queries.push(request_status_sign(
+                agent.clone(),
+                request_id,
+                canister_id,
+                expiry + query as u64 * MAX_INGRESS_EXPIRY_SECONDS * 1_000_000_000,
+            )?);
+        }
+        copies.push(IngressWithRequestId {
+            ingress: msg_with_req_id.message,
+            request_status,
+            later_status_queries,
+            later_copies: Vec::new(),
+        });
+    }
+    let mut message = copies.remove(0);
+    message.later_copies = copies;
     Ok(message)
 }
 
-/// Generates a signed ingress message.
+/// Generates a signed ingress message, with copies for the following validity windows.
 pub fn sign_ingress(
     agent: Agent,
     canister_id: Principal,
@@ -162,6 +282,19 @@ pub fn sign_ingress(
     is_query: bool,
     args: Vec<u8>,
 ) -> AnyhowResult<Ingress> {
-    let msg = sign(agent, canister_id, method_name, is_query, args)?;
-    Ok(msg.message)
+    let mut copies = Vec::new();
+    for window in 0..windows() {
+        let msg = sign(
+            agent.clone(),
+            canister_id,
+            method_name,
+            is_query,
+            args.clone(),
+            window_expiry(window),
+        )?;
+        copies.push(msg.message);
+    }
+    let mut message = copies.remove(0);
+    message.later_copies = copies;
+    Ok(message)
 }
@@ -0,0 +1,598 @@
+//! Types and helpers for the governance and ledger canisters of an SNS.
+use crate::lib::{
+    duration::{format_duration, format_timestamp, now_seconds},
+    governance::format_e8s,
+    governance_canister_id, AnyhowResult,
+};
+use anyhow::anyhow;
+use candid::{CandidType, Decode, Deserialize, Principal};
+use std::convert::TryFrom;
+
+#[derive(CandidType, Deserialize, Clone)]
+pub struct NeuronId {
+    pub id: Vec<u8>,
+}
+
+#[derive(CandidType, Deserialize)]
+pub struct ProposalId {
+    pub id: u64,
+}
+
+#[derive(CandidType)]
+pub struct ManageNeuron {
+    pub subaccount: Vec<u8>,
+    pub command: Option<Command>,
+}
+
+#[derive(CandidType)]
+pub enum Command {
+    Split(Split),
+    Follow(Follow),
+    DisburseMaturity(DisburseMaturity),
+    ClaimOrRefresh(ClaimOrRefresh),
+    Configure(Configure),
+    RegisterVote(RegisterVote),
+    StakeMaturity(StakeMaturity),
+    RemoveNeuronPermissions(RemoveNeuronPermissions),
+    AddNeuronPermissions(AddNeuronPermissions),
+    Disburse(Disburse),
+}
+
+#[derive(CandidType)]
+pub struct Split {
+    pub memo: u64,
+    pub amount_e8s: u64,
+}
+
+#[derive(CandidType)]
+pub struct Follow {
+    pub function_id: u64,
+    pub followees: Vec<NeuronId>,
+}
+
+#[derive(CandidType)]
+pub struct DisburseMaturity {
+    pub to_account: Option<Account>,
+    pub percentage_to_disburse: u32,
+}
+
+#[derive(CandidType)]
+pub struct ClaimOrRefresh {
+    pub by: Option<By>,
+}
+
+#[derive(CandidType)]
+pub enum By {
+    MemoAndController(MemoAndController),
+    NeuronId(Empty),
+}
+
+#[derive(CandidType)]
+pub struct MemoAndController {
+    pub controller: Option<Principal>,
+    pub memo: u64,
+}
+
+#[derive(CandidType)]
+pub struct Configure {
+    pub operation: Option<Operation>,
+}
+
+#[derive(CandidType)]
+pub enum Operation {
+    ChangeAutoStakeMaturity(ChangeAutoStakeMaturity),
+    StopDissolving(Empty),
+    StartDissolving(Empty),
+    IncreaseDissolveDelay(IncreaseDissolveDelay),
+}
+
+#[derive(CandidType)]
+pub struct ChangeAutoStakeMaturity {
+    pub requested_setting_for_auto_stake_maturity: bool,
+}
+
+#[derive(CandidType)]
+pub struct IncreaseDissolveDelay {
+    pub additional_dissolve_delay_seconds: u32,
+}
+
+#[derive(CandidType)]
+pub struct RegisterVote {
+    pub vote: i32,
+    pub proposal: Option<ProposalId>,
+}
+
+#[derive(CandidType)]
+pub struct StakeMaturity {
+    pub percentage_to_stake: Option<u32>,
+}
+
+#[derive(CandidType)]
+pub struct NeuronPermissionList {
+    pub permissions: Vec<i32>,
+}
+
+#[derive(CandidType)]
+pub struct AddNeuronPermissions {
+    pub permissions_to_add: Option<NeuronPermissionList>,
+    pub principal_id: Option<Principal>,
+}
+
+#[derive(CandidType)]
+pub struct RemoveNeuronPermissions {
+    pub permissions_to_remove: Option<NeuronPermissionList>,
+    pub principal_id: Option<Principal>,
+}
+
+#[derive(CandidType)]
+pub struct Disburse {
+    pub to_account: Option<Account>,
+    pub amount: Option<Amount>,
+}
+
+#[derive(CandidType)]
+pub struct Account {
+    pub owner: Option<Principal>,
+    pub subaccount: Option<Subaccount>,
+}
+
+#[derive(CandidType)]
+pub struct Subaccount {
+    pub subaccount: Vec<u8>,
+}
+
+#[derive(CandidType)]
+pub struct Amount {
+    pub e8s: u64,
+}
+
+#[derive(CandidType, Deserialize)]
+pub struct Empty {}
+
+#[derive(CandidType)]
+pub struct ListNeurons {
+    pub of_principal: Option<Principal>,
+    pub limit: u32,
+    pub start_page_at: Option<NeuronId>,
+}
+
+/// The subset of the SNS `Neuron` record shown by `send`.
+#[derive(CandidType, Deserialize)]
+pub struct Neuron {
+    pub id: Option<NeuronId>,
+    pub permissions: Vec<NeuronPermission>,
+    pub maturity_e8s_equivalent: u64,
+    pub staked_maturity_e8s_equivalent: Option<u64>,
+    pub cached_neuron_stake_e8s: u64,
+    pub neuron_fees_e8s: u64,
+    pub aging_since_timestamp_seconds: u64,
+    pub dissolve_state: Option<DissolveState>,
+    pub followees: Vec<(u64, Followees)>,
+}
+
+#[derive(CandidType, Deserialize)]
+pub struct NeuronPermission {
+    pub principal: Option<Principal>,
+    pub permission_type: Vec<i32>,
+}
+
+#[derive(CandidType, Deserialize)]
+pub enum DissolveState {
+    DissolveDelaySeconds(u64),
+    WhenDissolvedTimestampSeconds(u64),
+}
+
+#[derive(CandidType, Deserialize)]
+pub struct Followees {
+    pub followees: Vec<NeuronId>,
+}
+
+#[derive(CandidType, Deserialize)]
+pub struct ListNeuronsResponse {
+    pub neurons: Vec<Neuron>,
+}
+
+#[derive(CandidType, Deserialize)]
+pub struct GovernanceError {
+    pub error_message: String,
+    pub error_type: i32,
+}
+
+#[derive(CandidType, Deserialize)]
+pub struct ManageNeuronResponse {
+    pub command: Option<CommandResponse>,
+}
+
+#[derive(CandidType, Deserialize)]
+pub enum CommandResponse {
+    Error(GovernanceError),
+    Split(SplitResponse),
+    Follow(Empty),
+    DisburseMaturity(DisburseMaturityResponse),
+    ClaimOrRefresh(ClaimOrRefreshResponse),
+    Configure(Empty),
+    RegisterVote(Empty),
+    MakeProposal(Empty),
+    RemoveNeuronPermission(Empty),
+    StakeMaturity(StakeMaturityResponse),
+    MergeMaturity(Empty),
+    Disburse(DisburseResponse),
+    AddNeuronPermission(Empty),
+}
+
+#[derive(CandidType, Deserialize)]
+pub struct SplitResponse {
+    pub created_neuron_id: Option<NeuronId>,
+}
+
+#[derive(CandidType, Deserialize)]
+pub struct ClaimOrRefreshResponse {
+    pub refreshed_neuron_id: Option<NeuronId>,
+}
+
+#[derive(CandidType, Deserialize)]
+pub struct DisburseMaturityResponse {
+    pub amount_disbursed_e8s: u64,
+}
+
+#[derive(CandidType, Deserialize)]
+pub struct StakeMaturityResponse {
+    pub maturity_e8s: u64,
+    pub staked_maturity_e8s: u64,
+}
+
+#[derive(CandidType, Deserialize)]
+pub struct DisburseResponse {
+    pub transfer_block_height: u64,
+}
+
+/// The argument of the swap's `refresh_buyer_tokens`.
+#[derive(CandidType)]
+pub struct RefreshBuyerTokensRequest {
+    pub confirmation_text: Option<String>,
+    pub buyer: String,
+}
+
+#[derive(CandidType, Deserialize)]
+pub struct RefreshBuyerTokensResponse {
+    pub icp_accepted_participation_e8s: u64,
+    pub icp_ledger_account_balance_e8s: u64,
+}
+
+/// The argument of the swap's `new_sale_ticket`.
+#[derive(CandidType)]
+pub struct NewSaleTicketRequest {
+    pub amount_icp_e8s: u64,
+    pub subaccount: Option<Vec<u8>>,
+}
+
+#[derive(CandidType, Deserialize)]
+pub struct NewSaleTicketResponse {
+    pub result: Option<NewSaleTicketResult>,
+}
+
+#[derive(CandidType, Deserialize)]
+pub enum NewSaleTicketResult {
+    Ok(TicketOk),
+    Err(TicketErr),
+}
+
+#[derive(CandidType, Deserialize)]
+pub struct TicketOk {
+    pub ticket: Option<Ticket>,
+}
+
+#[derive(CandidType, Deserialize)]
+pub struct TicketErr {
+    pub invalid_user_amount: Option<InvalidUserAmount>,
+    pub existing_ticket: Option<Ticket>,
+    pub error_type: i32,
+}
+
+#[derive(CandidType, Deserialize)]
+pub struct InvalidUserAmount {
+    pub min_amount_icp_e8s_included: u64,
+    pub max_amount_icp_e8s_included: u64,
+}
+
+#[derive(CandidType, Deserialize)]
+pub struct GetOpenTicketResponse {
+    pub result: Option<GetOpenTicketResult>,
+}
+
+#[derive(CandidType, Deserialize)]
+pub enum GetOpenTicketResult {
+    Ok(TicketOk),
+    Err(GetOpenTicketErr),
+}
+
+#[derive(CandidType, Deserialize)]
+pub struct GetOpenTicketErr {
+    pub error_type: Option<i32>,
+}
+
+#[derive(CandidType, Deserialize)]
+pub struct Ticket {
+    pub creation_time: u64,
+    pub ticket_id: u64,
+    pub amount_icp_e8s: u64,
+}
+
+/// Swap ticket error types, indexed by their numeric value.
+const TICKET_ERROR_TYPES: &[&str] = &[
+    "Unspecified",
+    "SaleNotOpen",
+    "SaleClosed",
+    "TicketExists",
+    "InvalidUserAmount",
+    "InvalidSubaccount",
+    "InvalidPrincipal",
+];
+
+/// SNS neuron permissions, indexed by their numeric value.
+const PERMISSIONS: &[&str] = &[
+    "Unspecified",
+    "ConfigureDissolveState",
+    "ManagePrincipals",
+    "SubmitProposal",
+    "Vote",
+    "Disburse",
+    "Split",
+    "MergeMaturity",
+    "DisburseMaturity",
+    "StakeMaturity",
+    "ManageVotingPermission",
+];
+
+/// SNS governance error types, indexed by their numeric value.
+const ERROR_TYPES: &[&str] = &[
+    "Unspecified",
+    "Unavailable",
+    "NotAuthorized",
+    "NotFound",
+    "InvalidCommand",
+    "RequiresNotDissolving",
+    "RequiresDissolving",
+    "RequiresDissolved",
+    "AccessControlList",
+    "ResourceExhausted",
+    "PreconditionFailed",
+    "External",
+    "NeuronLocked",
+    "InsufficientFunds",
+    "InvalidPrincipal",
+    "InvalidProposal",
+    "InvalidNeuronId",
+];
+
+/// Parses a neuron permission given either by name (e.g. `vote`, `submit-proposal`) or by number.
+pub fn parse_permission(permission: &str) -> AnyhowResult<i32> {
+    if let Ok(number) = permission.parse::<i32>() {
+        return Ok(number);
+    }
+    let normalized = permission.replace(&['-', '_', ' '][..], "").to_lowercase();
+    PERMISSIONS
+        .iter()
+        .position(|name| name.to_lowercase() == normalized)
+        .map(|index| index as i32)
+        .ok_or_else(|| anyhow!("Unknown neuron permission {}", permission))
+}
+
+fn name(names: &[&str], value: i32) -> String {
+    usize::try_from(value)
+        .ok()
+        .and_then(|index| names.get(index))
+        .map_or_else(|| value.to_string(), |name| name.to_string())
+}
+
+/// Parses an SNS neuron id, which is given in hex.
+pub fn parse_neuron_id(id: &str) -> AnyhowResult<Vec<u8>> {
+    let bytes = hex::decode(id.trim_start_matches("0x"))
+        .map_err(|err| anyhow!("Couldn't parse the SNS neuron id {}: {}", id, err))?;
+    if bytes.len() != 32 {
+        return Err(anyhow!("SNS neuron ids must be 32 bytes long"));
+    }
+    Ok(bytes)
+}
+
+fn render_error(error: &GovernanceError) -> String {
+    format!(
+        "Error: {} (type {}): {}",
+        name(ERROR_TYPES, error.error_type),
+        error.error_type,
+        error.error_message
+    )
+}
+
+fn render_ticket(ticket: &Ticket) -> String {
+    format!(
+        "Ticket {} for {} ICP, created on {}",
+        ticket.ticket_id,
+        format_e8s(ticket.amount_icp_e8s),
+        format_timestamp(ticket.creation_time / 1_000_000_000)
+    )
+}
+
+fn render_neuron(neuron: &Neuron) -> String {
+    let mut lines = vec![
+        format!(
+            "Neuron id:       {}",
+            neuron
+                .id
+                .as_ref()
+                .map_or_else(|| "-".to_string(), |id| hex::encode(&id.id))
+        ),
+        format!(
+            "Stake:           {}",
+            format_e8s(
+                neuron
+                    .cached_neuron_stake_e8s
+                    .saturating_sub(neuron.neuron_fees_e8s)
+            )
+        ),
+        format!(
+            "Maturity:        {}",
+            format_e8s(neuron.maturity_e8s_equivalent)
+        ),
+        format!(
+            "Staked maturity: {}",
+            format_e8s(neuron.staked_maturity_e8s_equivalent.unwrap_or_default())
+        ),
+        format!(
+            "Dissolve state:  {}",
+            match neuron.dissolve_state {
+                Some(DissolveState::DissolveDelaySeconds(0)) | None => "Dissolved".to_string(),
+                Some(DissolveState::DissolveDelaySeconds(delay)) => {
+                    format!("Locked, dissolve delay {}", format_duration(delay))
+                }
+                Some(DissolveState::WhenDissolvedTimestampSeconds(timestamp))
+                    if timestamp > now_seconds() =>
+                {
+                    format!("Dissolving, dissolved on {}", format_timestamp(timestamp))
+                }
+                Some(DissolveState::WhenDissolvedTimestampSeconds(_)) => "Dissolved".to_string(),
+            }
+        ),
+    ];
+    for permission in &neuron.permissions {
+        lines.push(format!(
+            "Permissions:     {}: {}",
+            permission
+                .principal
+                .as_ref()
+                .map_or_else(|| "-".to_string(), |principal| principal.to_string()),
+            permission
+                .permission_type
+                .iter()
+                .map(|permission| name(PERMISSIONS, *permission))
+                .collect::<Vec<_>>()
+                .join(", ")
+        ));
+    }
+    for (function_id, followees) in &neuron.followees {
+        lines.push(format!(
+            "Follows on {}:   {}",
+            function_id,
+            followees
+                .followees
+                .iter()
+                .map(|id| hex::encode(&id.id))
+                .collect::<Vec<_>>()
+                .join(", ")
+        ));
+    }
+    lines.join("\n")
+}
+
+/// Renders the reply of an SNS governance query, or returns `None` if the reply isn't
+/// one of an SNS governance canister.
+pub fn render_reply(canister_id: Principal, method_name: &str, blob: &[u8]) -> Option<String> {
+    if canister_id == governance_canister_id() {
+        return None;
+    }
+    match method_name {
+        "list_neurons" => {
+            let response = Decode!(blob, ListNeuronsResponse).ok()?;
+            if response.neurons.is_empty() {
+                return Some("No neurons found".to_string());
+            }
+            Some(
+                response
+                    .neurons
+                    .iter()
+                    .map(render_neuron)
+                    .collect::<Vec<_>>()
+                    .join("\n\n"),
+            )
+        }
+        "get_open_ticket" => Some(match Decode!(blob, GetOpenTicketResponse).ok()?.result? {
+            GetOpenTicketResult::Ok(TicketOk {
+                ticket: Some(ticket),
+            }) => render_ticket(&ticket),
+            GetOpenTicketResult::Ok(TicketOk { ticket: None }) => "No open ticket".to_string(),
+            GetOpenTicketResult::Err(err) => format!(
+                "Error: {}",
+                name(TICKET_ERROR_TYPES, err.error_type.unwrap_or_default())
+            ),
+        }),
+        _ => None,
+    }
+}
+
+/// Describes the outcome of a call to an SNS governance or swap canister, like
+/// [`crate::lib::governance::describe_update_reply`] does for the NNS.
+pub fn describe_update_reply(
+    canister_id: Principal,
+    method_name: &str,
+    blob: &[u8],
+) -> Option<Result<String, String>> {
+    if canister_id == governance_canister_id() {
+        return None;
+    }
+    match method_name {
+        "manage_neuron" => describe_manage_neuron_reply(blob),
+        "refresh_buyer_tokens" => {
+            let response = Decode!(blob, RefreshBuyerTokensResponse).ok()?;
+            Some(Ok(format!(
+                "Participation: {} ICP accepted, {} ICP in the buyer's swap account",
+                format_e8s(response.icp_accepted_participation_e8s),
+                format_e8s(response.icp_ledger_account_balance_e8s)
+            )))
+        }
+        "new_sale_ticket" => match Decode!(blob, NewSaleTicketResponse).ok()?.result? {
+            NewSaleTicketResult::Ok(TicketOk { ticket }) => Some(Ok(ticket
+                .as_ref()
+                .map_or_else(|| "OK".to_string(), render_ticket))),
+            NewSaleTicketResult::Err(err) => {
+                let mut message = format!("Error: {}", name(TICKET_ERROR_TYPES, err.error_type));
+                if let Some(amount) = err.invalid_user_amount {
+                    message.push_str(&format!(
+                        ", the amount must be between {} and {} ICP",
+                        format_e8s(amount.min_amount_icp_e8s_included),
+                        format_e8s(amount.max_amount_icp_e8s_included)
+                    ));
+                }
+                if let Some(ticket) = err.existing_ticket {
+                    message.push_str(&format!(
+                        ", an open ticket exists: {}",
+                        render_ticket(&ticket)
+                    ));
+                }
+                Some(Err(message))
+            }
+        },
+        _ => None,
+    }
+}
+
+fn describe_manage_neuron_reply(blob: &[u8]) -> Option<Result<String, String>> {
+    let neuron_id = |id: &Option<NeuronId>| {
+        id.as_ref()
+            .map_or_else(|| "-".to_string(), |id| hex::encode(&id.id))
+    };
+    Some(match Decode!(blob, ManageNeuronResponse).ok()?.command? {
+        CommandResponse::Error(error) => Err(render_error(&error)),
+        CommandResponse::Split(response) => Ok(format!(
+            "Split off neuron {}",
+            neuron_id(&response.created_neuron_id)
+        )),
+        CommandResponse::ClaimOrRefresh(response) => Ok(format!(
+            "Refreshed neuron {}",
+            neuron_id(&response.refreshed_neuron_id)
+        )),
+        CommandResponse::DisburseMaturity(response) => Ok(format!(
+            "Disbursing {} tokens of maturity",
+            format_e8s(response.amount_disbursed_e8s)
+        )),
+        CommandResponse::StakeMaturity(response) => Ok(format!(
+            "Staked maturity is now {}, with {} of maturity left",
+            format_e8s(response.staked_maturity_e8s),
+            format_e8s(response.maturity_e8s)
+        )),
+        CommandResponse::Disburse(response) => Ok(format!(
+            "Disbursed in block {}",
+            response.transfer_block_height
+        )),
+        _ => Ok("OK".to_string()),
+    })
+}
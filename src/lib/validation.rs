@@ -0,0 +1,34 @@
+//! Offline checks of messages against the rules of the protocol, run before signing.
+use crate::lib::AnyhowResult;
+use anyhow::anyhow;
+
+/// The minimum stake of a neuron (1 ICP).
+pub const MIN_STAKE_E8S: u64 = 100_000_000;
+
+/// A problem found in a message about to be signed.
+pub enum Finding {
+    /// The message is rejected by the protocol or can't do what was asked.
+    Error(String),
+    /// The message is valid but most likely a mistake.
+    Warning(String),
+}
+
+/// Prints the warnings and fails if any error was found, unless `force` is set, in which
+/// case the errors are only printed.
+pub fn check(findings: Vec<Finding>, force: bool) -> AnyhowResult {
+    let mut errors = Vec::new();
+    for finding in findings {
+        match finding {
+            Finding::Warning(message) => eprintln!("Warning: {}", message),
+            Finding::Error(message) if force => eprintln!("Error (ignored): {}", message),
+            Finding::Error(message) => errors.push(message),
+        }
+    }
+    if errors.is_empty() {
+        return Ok(());
+    }
+    Err(anyhow!(
+        "Nothing was signed:\n  {}\nUse --force to sign anyway.",
+        errors.join("\n  ")
+    ))
+}
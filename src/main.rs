@@ -16,6 +16,26 @@ pub struct CliOpts {
     #[clap(long)]
     pem_file: Option<String>,
 
+    /// How long signed messages are valid, up to the limit of 5 minutes (e.g. "3m" or "90s").
+    #[clap(long, default_value = "5m")]
+    ingress_expiry: String,
+
+    /// Sign copies of every message for this many consecutive validity windows, so that
+    /// the messages can be sent later: `send` picks the copy valid at the time.
+    #[clap(long, default_value = "1")]
+    expiry_windows: u32,
+
+    /// Sign this many request status queries for every message, each valid 5 minutes
+    /// longer than the previous one, so that `send` can fetch the outcome of a message
+    /// long after it was sent.
+    #[clap(long, default_value = "1")]
+    status_queries: u32,
+
+    /// The current time as an RFC 3339 date (e.g. 2025-05-01T12:00:00Z), for machines
+    /// whose clock isn't trusted.
+    #[clap(long)]
+    now: Option<String>,
+
     #[clap(subcommand)]
     command: commands::Command,
 }
@@ -33,8 +53,17 @@ fn main() {
             )
         }),
     };
-    if let Err(err) = commands::exec(pem, command) {
+    let expiry = commands::ExpiryOpts {
+        ingress_expiry: opts.ingress_expiry,
+        windows: opts.expiry_windows,
+        status_queries: opts.status_queries,
+        now: opts.now,
+    };
+    if let Err(err) = commands::exec(pem, expiry, command) {
         eprintln!("{}", err);
+        if err.is::<lib::request_status::OutcomeUnknown>() {
+            std::process::exit(lib::request_status::OUTCOME_UNKNOWN_EXIT_CODE);
+        }
         std::process::exit(1);
     }
 }
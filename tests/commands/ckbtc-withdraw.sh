@@ -0,0 +1 @@
+${CARGO_TARGET_DIR:-../target}/debug/qu --seed-file - ckbtc withdraw --address bc1qar0srrr7xfkvy5l643lydnw9re59gtzzwf5mdq --amount 0.001 | ${CARGO_TARGET_DIR:-../target}/debug/qu send --dry-run -
@@ -0,0 +1 @@
+${CARGO_TARGET_DIR:-../target}/debug/qu --seed-file - claim-gtc-neurons 123 | ${CARGO_TARGET_DIR:-../target}/debug/qu send --dry-run -
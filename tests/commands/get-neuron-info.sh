@@ -0,0 +1 @@
+${CARGO_TARGET_DIR:-../target}/debug/qu --seed-file - get-neuron-info 2_313_380_519_530_470_538 --full | ${CARGO_TARGET_DIR:-../target}/debug/qu send --dry-run -
@@ -0,0 +1 @@
+${CARGO_TARGET_DIR:-../target}/debug/qu --seed-file - get-proposal-info 12345 | ${CARGO_TARGET_DIR:-../target}/debug/qu send --dry-run -
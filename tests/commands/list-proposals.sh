@@ -0,0 +1 @@
+${CARGO_TARGET_DIR:-../target}/debug/qu --seed-file - list-proposals --status open --limit 5 | ${CARGO_TARGET_DIR:-../target}/debug/qu send --dry-run -
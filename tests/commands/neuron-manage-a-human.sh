@@ -0,0 +1 @@
+${CARGO_TARGET_DIR:-../target}/debug/qu --seed-file - neuron-manage 2313380519530470538 -a "1y 3d" | ${CARGO_TARGET_DIR:-../target}/debug/qu send --dry-run -
@@ -0,0 +1 @@
+${CARGO_TARGET_DIR:-../target}/debug/qu --seed-file - neuron-manage 2313380519530470538 --join-community-fund | ${CARGO_TARGET_DIR:-../target}/debug/qu send --dry-run -
@@ -0,0 +1 @@
+${CARGO_TARGET_DIR:-../target}/debug/qu --seed-file - neuron-manage 123 456 --stop-dissolving | ${CARGO_TARGET_DIR:-../target}/debug/qu send --dry-run -
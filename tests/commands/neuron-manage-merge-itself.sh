@@ -0,0 +1 @@
+${CARGO_TARGET_DIR:-../target}/debug/qu --seed-file - neuron-manage 2313380519530470538 --merge-from-neuron 2313380519530470538 2>&1 || true
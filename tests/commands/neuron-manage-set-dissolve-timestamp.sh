@@ -0,0 +1 @@
+${CARGO_TARGET_DIR:-../target}/debug/qu --now 2025-05-01T00:00:00Z --seed-file - neuron-manage 2313380519530470538 --set-dissolve-timestamp 2026-05-01T00:00:00Z | ${CARGO_TARGET_DIR:-../target}/debug/qu --now 2025-05-01T00:00:00Z send --dry-run -
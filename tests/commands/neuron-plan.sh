@@ -0,0 +1 @@
+${CARGO_TARGET_DIR:-../target}/debug/qu --seed-file - neuron-plan plan.toml | ${CARGO_TARGET_DIR:-../target}/debug/qu send --dry-run -
@@ -0,0 +1,6 @@
+message=$(mktemp)
+${CARGO_TARGET_DIR:-../target}/debug/qu --now 2025-05-01T00:00:00Z --expiry-windows 3 --seed-file - neuron-manage 2313380519530470538 --start-dissolving > "$message"
+for now in 00:00:00 00:05:00 00:10:00 00:20:00; do
+    ${CARGO_TARGET_DIR:-../target}/debug/qu --now 2025-05-01T${now}Z send --dry-run --show-envelope "$message" 2>&1
+done | sed -e 's/\(Nonce: *\).*/\1<nonce>/' -e 's/0x[0-9a-f]*/0x<request id>/' -e 's#/request_status/[0-9a-f]*#/request_status/<request id>#'
+rm -f "$message"
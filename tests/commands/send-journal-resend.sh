@@ -0,0 +1,6 @@
+message=$(mktemp)
+journal=$(mktemp)
+${CARGO_TARGET_DIR:-../target}/debug/qu --seed-file - transfer 345f723e9e619934daac6ae0f4be13a7b0ba57d6a608e511a00fd0ded5866752 --amount 0.000123 > "$message"
+${CARGO_TARGET_DIR:-../target}/debug/qu send --dry-run --json --journal "$journal" "$message" | sed 's/.*"request_id":"\([0-9a-f]*\)".*/{"request_id":"\1","canister_id":"ryjl3-tyaaa-aaaaa-aaaba-cai","method_name":"send_dfx","time":1650000000,"state":"replied"}/' > "$journal"
+${CARGO_TARGET_DIR:-../target}/debug/qu send --yes --journal "$journal" "$message" 2>&1 || true
+rm -f "$message" "$journal"
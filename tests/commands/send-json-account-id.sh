@@ -0,0 +1 @@
+${CARGO_TARGET_DIR:-../target}/debug/qu --seed-file - neuron-plan plan-disburse.toml | ${CARGO_TARGET_DIR:-../target}/debug/qu send --dry-run --json - | sed 's/"request_id":"[0-9a-f]*"/"request_id":"<request id>"/'
@@ -0,0 +1 @@
+${CARGO_TARGET_DIR:-../target}/debug/qu --seed-file - transfer 345f723e9e619934daac6ae0f4be13a7b0ba57d6a608e511a00fd0ded5866752 --amount 0.000123 | ${CARGO_TARGET_DIR:-../target}/debug/qu send --dry-run --json - | sed 's/"request_id":"[0-9a-f]*"/"request_id":"<request id>"/'
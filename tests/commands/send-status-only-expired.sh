@@ -0,0 +1 @@
+${CARGO_TARGET_DIR:-../target}/debug/qu --now 2021-06-01T00:00:00Z --seed-file - transfer 345f723e9e619934daac6ae0f4be13a7b0ba57d6a608e511a00fd0ded5866752 --amount 0.000123 | ${CARGO_TARGET_DIR:-../target}/debug/qu send --status-only - 2>&1 | sed 's/request 0x[0-9a-f]*/request 0x<request id>/' || true
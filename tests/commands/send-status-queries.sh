@@ -0,0 +1 @@
+${CARGO_TARGET_DIR:-../target}/debug/qu --now 2025-05-01T00:00:00Z --status-queries 3 --seed-file - neuron-manage 2313380519530470538 --start-dissolving | ${CARGO_TARGET_DIR:-../target}/debug/qu --now 2025-05-01T00:00:00Z send --dry-run --show-envelope - | sed -e 's/\(Nonce: *\).*/\1<nonce>/' -e 's/0x[0-9a-f]*/0x<request id>/' -e 's#/request_status/[0-9a-f]*#/request_status/<request id>#'
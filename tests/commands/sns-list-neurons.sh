@@ -0,0 +1 @@
+${CARGO_TARGET_DIR:-../target}/debug/qu --seed-file - sns --governance-canister-id 2jvtu-yqaaa-aaaaq-aaama-cai list-neurons --limit 10 | ${CARGO_TARGET_DIR:-../target}/debug/qu send --dry-run -
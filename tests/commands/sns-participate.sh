@@ -0,0 +1 @@
+${CARGO_TARGET_DIR:-../target}/debug/qu --seed-file - sns-participate --swap-canister-id 2ouva-viaaa-aaaaq-aaamq-cai --amount 5 | ${CARGO_TARGET_DIR:-../target}/debug/qu send --dry-run -
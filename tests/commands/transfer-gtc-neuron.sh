@@ -0,0 +1 @@
+${CARGO_TARGET_DIR:-../target}/debug/qu --seed-file - transfer-gtc-neuron 123 456 | ${CARGO_TARGET_DIR:-../target}/debug/qu send --dry-run -